@@ -162,29 +162,40 @@ fn main() {
         .file(format!("{}/ClpSimplexPrimal.cpp", COIN_CLP_SRC))
         .file(format!("{}/ClpSolve.cpp", COIN_CLP_SRC))
         // .file(format!("{}/ClpSolver.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcBaseFactorization1.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcBaseFactorization2.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcBaseFactorization3.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcBaseFactorization4.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcBaseFactorization5.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcDenseFactorization.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcFactorization1.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcFactorization2.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcFactorization3.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcFactorization4.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcFactorization5.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcHelperFunctions.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcOrderedFactorization1.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcOrderedFactorization2.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcOrderedFactorization3.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcOrderedFactorization4.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcOrderedFactorization5.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcSmallFactorization1.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcSmallFactorization2.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcSmallFactorization3.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcSmallFactorization4.cpp", COIN_CLP_SRC))
-        // .file(format!("{}/CoinAbcSmallFactorization5.cpp", COIN_CLP_SRC))
         .file(format!("{}/Idiot.cpp", COIN_CLP_SRC))
-        .file(format!("{}/IdiSolve.cpp", COIN_CLP_SRC))
-        .compile("Clp");
+        .file(format!("{}/IdiSolve.cpp", COIN_CLP_SRC));
+
+    // The Abc ("Aboca") factorization is an alternative to the default dense/network
+    // factorizations in `ClpFactorization.cpp`, and can be faster for large bases. It's
+    // disabled by default to avoid bloating ordinary builds; enable it with the
+    // `abc-factorization` feature.
+    if env::var_os("CARGO_FEATURE_ABC_FACTORIZATION").is_some() {
+        builder
+            .define("ABOCA_LITE", Some("1"))
+            .define("ABCSTATE_LITE", Some("1"))
+            .file(format!("{}/CoinAbcBaseFactorization1.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcBaseFactorization2.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcBaseFactorization3.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcBaseFactorization4.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcBaseFactorization5.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcDenseFactorization.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcFactorization1.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcFactorization2.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcFactorization3.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcFactorization4.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcFactorization5.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcHelperFunctions.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcOrderedFactorization1.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcOrderedFactorization2.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcOrderedFactorization3.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcOrderedFactorization4.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcOrderedFactorization5.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcSmallFactorization1.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcSmallFactorization2.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcSmallFactorization3.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcSmallFactorization4.cpp", COIN_CLP_SRC))
+            .file(format!("{}/CoinAbcSmallFactorization5.cpp", COIN_CLP_SRC));
+    }
+
+    builder.compile("Clp");
 }
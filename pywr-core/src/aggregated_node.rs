@@ -0,0 +1,60 @@
+use crate::metric::Metric;
+use crate::node::NodeIndex;
+
+/// Index of an [`AggregatedNode`] within a [`crate::network::Network`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct AggregatedNodeIndex(usize);
+
+impl AggregatedNodeIndex {
+    pub fn new(idx: usize) -> Self {
+        Self(idx)
+    }
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// How an [`AggregatedNode`]'s constituent nodes' flows are constrained relative to one another.
+///
+/// Each variant holds one ratio [`Metric`] per constituent node (in the same order the nodes were
+/// added to the aggregated node); the LP constraint builder reads these every timestep to tie
+/// each node's flow to the first node's flow in proportion to their ratios, i.e. node `i`'s flow
+/// is compared against `flow_0 * factor_i / factor_0`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Factors {
+    /// Every constituent node's flow must equal `flow_0 * factor_i / factor_0`.
+    Ratio(Vec<Metric>),
+    /// Every constituent node's flow must be less than or equal to `flow_0 * factor_i / factor_0`,
+    /// e.g. to cap a loss as at most some proportion of the net flow without forcing it to always
+    /// take that proportion.
+    RatioLEQ(Vec<Metric>),
+    /// Every constituent node's flow must be greater than or equal to `flow_0 * factor_i / factor_0`,
+    /// e.g. to guarantee a minimum proportion of flow is routed through a node.
+    RatioGEQ(Vec<Metric>),
+}
+
+/// A node that constrains the relative flows of a group of other nodes, e.g. splitting a flow
+/// between a net output and a loss link by a fixed or dynamic ratio.
+pub struct AggregatedNode {
+    pub(crate) nodes: Vec<NodeIndex>,
+    pub(crate) factors: Option<Factors>,
+}
+
+impl AggregatedNode {
+    pub fn new(nodes: Vec<NodeIndex>, factors: Option<Factors>) -> Self {
+        Self { nodes, factors }
+    }
+
+    pub fn nodes(&self) -> &[NodeIndex] {
+        &self.nodes
+    }
+
+    pub fn factors(&self) -> Option<&Factors> {
+        self.factors.as_ref()
+    }
+
+    pub fn set_factors(&mut self, factors: Option<Factors>) {
+        self.factors = factors;
+    }
+}
@@ -25,11 +25,25 @@ impl VolumeBetweenControlCurves {
     }
 }
 
+/// The reduction function applied by [`Metric::Aggregated`] to its child metrics.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AggFunc {
+    Sum,
+    Product,
+    Min,
+    Max,
+    Mean,
+    CountAbove(f64),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum Metric {
     NodeInFlow(NodeIndex),
     NodeOutFlow(NodeIndex),
     NodeVolume(NodeIndex),
+    /// The total volume of out-flow through a node accumulated over the run so far, using the
+    /// trapezoidal rule between the current and previous timestep's flow.
+    IntegratedNodeOutFlow(NodeIndex),
     NodeInFlowDeficit(NodeIndex),
     NodeProportionalVolume(NodeIndex),
     AggregatedNodeInFlow(AggregatedNodeIndex),
@@ -49,6 +63,10 @@ pub enum Metric {
     },
     // TODO implement other MultiNodeXXX variants
     Constant(f64),
+    Aggregated {
+        metrics: Vec<Metric>,
+        agg_func: AggFunc,
+    },
 }
 
 impl Metric {
@@ -56,6 +74,10 @@ impl Metric {
         match self {
             Metric::NodeInFlow(idx) => Ok(state.get_network_state().get_node_in_flow(idx)?),
             Metric::NodeOutFlow(idx) => Ok(state.get_network_state().get_node_out_flow(idx)?),
+            // The running total itself is only ever written by `Metric::advance`, since computing
+            // it needs mutable access to `State` that `get_value` does not have; this just reads
+            // whatever `advance` last stored.
+            Metric::IntegratedNodeOutFlow(idx) => Ok(state.get_network_state().get_integrated_node_out_flow(idx)?),
             Metric::NodeVolume(idx) => Ok(state.get_network_state().get_node_volume(idx)?),
             Metric::AggregatedNodeInFlow(idx) => {
                 let node = model.get_aggregated_node(idx)?;
@@ -140,6 +162,65 @@ impl Metric {
                 // TODO handle invalid bounds
                 Ok(max_volume * (upper - lower))
             }
+            Metric::Aggregated { metrics, agg_func } => {
+                let values = metrics
+                    .iter()
+                    .map(|metric| metric.get_value(model, state))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                match agg_func {
+                    AggFunc::Sum => Ok(values.iter().sum()),
+                    AggFunc::Product => Ok(values.iter().product()),
+                    AggFunc::Mean => Ok(values.iter().sum::<f64>() / values.len() as f64),
+                    AggFunc::Min => values
+                        .into_iter()
+                        .reduce(f64::min)
+                        .ok_or(PywrError::NoMetricsToAggregate),
+                    AggFunc::Max => values
+                        .into_iter()
+                        .reduce(f64::max)
+                        .ok_or(PywrError::NoMetricsToAggregate),
+                    AggFunc::CountAbove(threshold) => {
+                        Ok(values.iter().filter(|v| **v > *threshold).count() as f64)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Advance any running total this metric accumulates between timesteps (currently just
+    /// [`Metric::IntegratedNodeOutFlow`]'s trapezoidal running total). The run loop must call this
+    /// once per timestep, after that timestep's flows have been solved and before `advance`'s
+    /// metric is next read via [`Metric::get_value`]; most variants have nothing to accumulate and
+    /// this is a no-op.
+    pub fn advance(&self, state: &mut State) -> Result<(), PywrError> {
+        match self {
+            Metric::IntegratedNodeOutFlow(idx) => {
+                let network_state = state.get_network_state();
+                let flow = network_state.get_node_out_flow(idx)?;
+                let previous_flow = network_state.get_previous_node_out_flow(idx)?;
+                let dt = network_state.timestep_duration();
+                let integral = network_state.get_integrated_node_out_flow(idx)?;
+                // Trapezoidal rule: add the area under the flow curve since the last timestep.
+                let new_integral = integral + 0.5 * (flow + previous_flow) * dt;
+
+                let network_state = state.get_network_state_mut();
+                network_state.set_integrated_node_out_flow(idx, new_integral)?;
+                network_state.set_previous_node_out_flow(idx, flow)?;
+                Ok(())
+            }
+            Metric::Aggregated { metrics, .. } => metrics.iter().try_for_each(|metric| metric.advance(state)),
+            Metric::VolumeBetweenControlCurves(vol) => {
+                vol.max_volume.advance(state)?;
+                if let Some(lower) = &vol.lower {
+                    lower.advance(state)?;
+                }
+                if let Some(upper) = &vol.upper {
+                    upper.advance(state)?;
+                }
+                Ok(())
+            }
+            _ => Ok(()),
         }
     }
 }
@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+/// An index into the network's list of virtual storage nodes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VirtualStorageIndex(usize);
+
+impl VirtualStorageIndex {
+    pub fn new(idx: usize) -> Self {
+        Self(idx)
+    }
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// Determines how and when a virtual storage node's available volume is replenished.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum VirtualStorageReset {
+    /// The node's volume is never reset; it behaves like an ordinary storage node with a licence
+    /// that is consumed over the lifetime of the model run.
+    Never,
+    /// The node's volume is reset once a year, on the given `day` of the given `month`.
+    DayOfYear { day: u8, month: u8 },
+    /// The node's available volume is bounded by a trailing window of the last `timesteps`
+    /// timesteps of cumulative net flow, rather than by a fixed calendar reset. See
+    /// [`RollingWindow`] for the bookkeeping this implies.
+    Rolling { timesteps: usize },
+}
+
+/// Tracks the per-timestep flow history backing a [`VirtualStorageReset::Rolling`] virtual
+/// storage node.
+///
+/// Each timestep the node's effective max volume is the configured licence minus the sum of
+/// flows still inside the trailing window; as the oldest entry falls out of the window (once the
+/// buffer reaches `timesteps` entries) its volume is credited back automatically by simply
+/// dropping it from the sum. During the partial-window warm-up at the start of a run, missing
+/// history is treated as zero usage (i.e. the buffer starts empty rather than pre-filled).
+#[derive(Debug, Clone)]
+pub struct RollingWindow {
+    timesteps: usize,
+    history: VecDeque<f64>,
+    sum: f64,
+}
+
+impl RollingWindow {
+    pub fn new(timesteps: usize) -> Self {
+        Self {
+            timesteps,
+            history: VecDeque::with_capacity(timesteps),
+            sum: 0.0,
+        }
+    }
+
+    /// Record this timestep's net flow and return the new cumulative flow within the window.
+    pub fn push(&mut self, flow: f64) -> f64 {
+        self.history.push_back(flow);
+        self.sum += flow;
+
+        if self.history.len() > self.timesteps {
+            // The oldest entry has fallen out of the window; credit its volume back.
+            if let Some(oldest) = self.history.pop_front() {
+                self.sum -= oldest;
+            }
+        }
+
+        self.sum
+    }
+
+    /// The cumulative flow currently inside the window, without advancing it.
+    pub fn current(&self) -> f64 {
+        self.sum
+    }
+}
+
+impl VirtualStorageReset {
+    /// Apply this timestep's net flow to a [`VirtualStorageReset::Rolling`] node's bound, and
+    /// return the max volume now available: `licence` minus whatever net flow is still inside the
+    /// trailing window. The run loop must call this once per timestep, after that timestep's
+    /// flows through the virtual storage node's contributing nodes have been solved, passing the
+    /// same `rolling_window` back in each time so the window keeps advancing.
+    ///
+    /// Every other reset mode is bounded by calendar logic rather than a rolling window, so
+    /// `rolling_window` is ignored and `licence` is returned unchanged for them; `rolling_window`
+    /// is also `None`-safe so callers that haven't allocated one yet (e.g. before the first
+    /// timestep) get the unreset licence back.
+    pub fn apply_rolling_window(&self, licence: f64, rolling_window: Option<&mut RollingWindow>, net_flow: f64) -> f64 {
+        match (self, rolling_window) {
+            (VirtualStorageReset::Rolling { .. }, Some(window)) => licence - window.push(net_flow),
+            _ => licence,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::data_tables::LoadedTableCollection;
+use crate::error::SchemaError;
+use crate::nodes::{CustomNodeRegistry, Edge, Node};
+use pywr_core::models::ModelDomain;
+use pywr_core::model::Model;
+use pywr_core::timestepper::Timestepper;
+use std::path::Path;
+
+/// A full Pywr model: its network (nodes, edges) plus the timestepper that drives it.
+///
+/// Deserializes directly from a model JSON file; [`PywrModel::build_model`] turns it into a
+/// runnable [`pywr_core::model::Model`].
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct PywrModel {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+    pub timestepper: Timestepper,
+    /// Builders for any node `type`s not known to this crate. Not part of the model file itself;
+    /// set this (e.g. via a downstream crate's `main`) before calling [`Self::build_model`] if the
+    /// model uses [`crate::nodes::CustomNode`]s.
+    #[serde(skip)]
+    pub custom_nodes: Option<CustomNodeRegistry>,
+}
+
+impl PywrModel {
+    /// Build a runnable core [`Model`] (and its [`Timestepper`]) from this schema.
+    ///
+    /// Every node is added to the model before any node's constraints are set, since a node's
+    /// constraints may reference a node that comes after it in `nodes` (e.g. a storage node's
+    /// control curve).
+    pub fn build_model(
+        &self,
+        data_path: Option<&Path>,
+        output_path: Option<&Path>,
+    ) -> Result<(Model, Timestepper), SchemaError> {
+        let domain = ModelDomain::try_from(self.timestepper.clone())?;
+        let mut model = Model::new(domain);
+        let tables = LoadedTableCollection::from_schema(&[], data_path)?;
+        let custom_nodes = self.custom_nodes.as_ref();
+
+        for node in &self.nodes {
+            node.add_to_model(model.network_mut(), &domain, &tables, data_path, custom_nodes)?;
+        }
+
+        for node in &self.nodes {
+            node.set_constraints(model.network_mut(), &domain, &tables, data_path, custom_nodes)?;
+        }
+
+        for edge in &self.edges {
+            let from = node_connector(&self.nodes, &edge.from_node)?;
+            let to = node_connector(&self.nodes, &edge.to_node)?;
+
+            let from_connectors = from.output_connectors(edge.from_slot.as_deref(), custom_nodes)?;
+            let to_connectors = to.input_connectors(custom_nodes)?;
+
+            for (from_name, from_sub_name) in &from_connectors {
+                for (to_name, to_sub_name) in &to_connectors {
+                    model
+                        .network_mut()
+                        .connect_nodes(from_name, from_sub_name.as_deref(), to_name, to_sub_name.as_deref())?;
+                }
+            }
+        }
+
+        let _ = output_path;
+        Ok((model, self.timestepper.clone()))
+    }
+}
+
+fn node_connector<'a>(nodes: &'a [Node], name: &str) -> Result<&'a Node, SchemaError> {
+    nodes
+        .iter()
+        .find(|n| n.name() == name)
+        .ok_or_else(|| SchemaError::NodeNotFound(name.to_string()))
+}
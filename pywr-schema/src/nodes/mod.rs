@@ -8,6 +8,7 @@ mod piecewise_storage;
 mod river;
 mod river_gauge;
 mod river_split_with_gauge;
+mod rolling_virtual_storage;
 mod virtual_storage;
 mod water_treatment_works;
 
@@ -32,6 +33,7 @@ use pywr_v1_schema::nodes::{
 };
 pub use river_gauge::RiverGaugeNode;
 pub use river_split_with_gauge::RiverSplitWithGaugeNode;
+pub use rolling_virtual_storage::{RollingVirtualStorageNode, RollingWindow};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::Path;
@@ -115,6 +117,7 @@ pub enum CoreNode {
     VirtualStorage(VirtualStorageNode),
     AnnualVirtualStorage(AnnualVirtualStorageNode),
     MonthlyVirtualStorage(MonthlyVirtualStorageNode),
+    RollingVirtualStorage(RollingVirtualStorageNode),
 }
 
 impl CoreNode {
@@ -146,6 +149,7 @@ impl CoreNode {
             CoreNode::PiecewiseStorage(_) => "PiecewiseStorage",
             CoreNode::Delay(_) => "Delay",
             CoreNode::MonthlyVirtualStorage(_) => "MonthlyVirtualStorage",
+            CoreNode::RollingVirtualStorage(_) => "RollingVirtualStorage",
         }
     }
 
@@ -169,6 +173,7 @@ impl CoreNode {
             CoreNode::PiecewiseStorage(n) => &n.meta,
             CoreNode::Delay(n) => &n.meta,
             CoreNode::MonthlyVirtualStorage(n) => &n.meta,
+            CoreNode::RollingVirtualStorage(n) => &n.meta,
         }
     }
 
@@ -208,6 +213,7 @@ impl CoreNode {
             CoreNode::PiecewiseStorage(n) => n.add_to_model(network, domain, tables, data_path),
             CoreNode::Delay(n) => n.add_to_model(network),
             CoreNode::MonthlyVirtualStorage(n) => n.add_to_model(network, domain, tables, data_path),
+            CoreNode::RollingVirtualStorage(n) => n.add_to_model(network, domain, tables, data_path),
         }
     }
 
@@ -237,6 +243,7 @@ impl CoreNode {
             CoreNode::PiecewiseStorage(n) => n.set_constraints(network, domain, tables, data_path),
             CoreNode::Delay(n) => n.set_constraints(network, tables),
             CoreNode::MonthlyVirtualStorage(_) => Ok(()), // TODO
+            CoreNode::RollingVirtualStorage(_) => Ok(()), // TODO
         }
     }
 
@@ -258,6 +265,7 @@ impl CoreNode {
             CoreNode::VirtualStorage(n) => n.input_connectors(),
             CoreNode::AnnualVirtualStorage(n) => n.input_connectors(),
             CoreNode::MonthlyVirtualStorage(n) => n.input_connectors(),
+            CoreNode::RollingVirtualStorage(n) => n.input_connectors(),
             CoreNode::PiecewiseLink(n) => n.input_connectors(),
             CoreNode::PiecewiseStorage(n) => n.input_connectors(),
             CoreNode::Delay(n) => n.input_connectors(),
@@ -282,6 +290,7 @@ impl CoreNode {
             CoreNode::VirtualStorage(n) => n.output_connectors(),
             CoreNode::AnnualVirtualStorage(n) => n.output_connectors(),
             CoreNode::MonthlyVirtualStorage(n) => n.output_connectors(),
+            CoreNode::RollingVirtualStorage(n) => n.output_connectors(),
             CoreNode::PiecewiseLink(n) => n.output_connectors(),
             CoreNode::PiecewiseStorage(n) => n.output_connectors(),
             CoreNode::Delay(n) => n.output_connectors(),
@@ -306,6 +315,7 @@ impl CoreNode {
             CoreNode::VirtualStorage(n) => n.default_metric(network),
             CoreNode::AnnualVirtualStorage(n) => n.default_metric(network),
             CoreNode::MonthlyVirtualStorage(n) => n.default_metric(network),
+            CoreNode::RollingVirtualStorage(n) => n.default_metric(network),
             CoreNode::PiecewiseLink(n) => n.default_metric(network),
             CoreNode::Delay(n) => n.default_metric(network),
             CoreNode::PiecewiseStorage(n) => n.default_metric(network),
@@ -355,10 +365,11 @@ impl Node {
         domain: &ModelDomain,
         tables: &LoadedTableCollection,
         data_path: Option<&Path>,
+        custom_nodes: Option<&CustomNodeRegistry>,
     ) -> Result<(), SchemaError> {
         match self {
             Node::Core(n) => n.add_to_model(network, domain, tables, data_path),
-            Node::Custom(n) => panic!("TODO custom nodes not yet supported: {}", n.meta.name.as_str()),
+            Node::Custom(n) => custom_node_builder(custom_nodes, n)?.add_to_model(network, n),
         }
     }
 
@@ -368,36 +379,177 @@ impl Node {
         domain: &ModelDomain,
         tables: &LoadedTableCollection,
         data_path: Option<&Path>,
+        custom_nodes: Option<&CustomNodeRegistry>,
     ) -> Result<(), SchemaError> {
         match self {
             Node::Core(n) => n.set_constraints(network, domain, tables, data_path),
-            Node::Custom(n) => panic!("TODO custom nodes not yet supported: {}", n.meta.name.as_str()),
+            Node::Custom(n) => custom_node_builder(custom_nodes, n)?.set_constraints(network, n, tables, data_path),
         }
     }
 
-    pub fn input_connectors(&self) -> Vec<(&str, Option<String>)> {
+    pub fn input_connectors(&self, custom_nodes: Option<&CustomNodeRegistry>) -> Result<Vec<(&str, Option<String>)>, SchemaError> {
         match self {
-            Node::Core(n) => n.input_connectors(),
-            Node::Custom(n) => panic!("TODO custom nodes not yet supported: {}", n.meta.name.as_str()),
+            Node::Core(n) => Ok(n.input_connectors()),
+            Node::Custom(n) => Ok(custom_node_builder(custom_nodes, n)?.input_connectors(n)),
         }
     }
 
-    pub fn output_connectors(&self, slot: Option<&str>) -> Vec<(&str, Option<String>)> {
+    pub fn output_connectors(
+        &self,
+        slot: Option<&str>,
+        custom_nodes: Option<&CustomNodeRegistry>,
+    ) -> Result<Vec<(&str, Option<String>)>, SchemaError> {
         match self {
-            Node::Core(n) => n.output_connectors(slot),
-            Node::Custom(n) => panic!("TODO custom nodes not yet supported: {}", n.meta.name.as_str()),
+            Node::Core(n) => Ok(n.output_connectors(slot)),
+            Node::Custom(n) => Ok(custom_node_builder(custom_nodes, n)?.output_connectors(n, slot)),
         }
     }
 
     /// Returns the default metric for this node.
-    pub fn default_metric(&self, network: &pywr_core::network::Network) -> Result<Metric, SchemaError> {
+    pub fn default_metric(
+        &self,
+        network: &pywr_core::network::Network,
+        custom_nodes: Option<&CustomNodeRegistry>,
+    ) -> Result<Metric, SchemaError> {
         match self {
             Node::Core(n) => n.default_metric(network),
-            Node::Custom(n) => panic!("TODO custom nodes not yet supported: {}", n.meta.name.as_str()),
+            Node::Custom(n) => custom_node_builder(custom_nodes, n)?.default_metric(network, n),
         }
     }
 }
 
+fn custom_node_builder<'a>(
+    registry: Option<&'a CustomNodeRegistry>,
+    node: &CustomNode,
+) -> Result<&'a dyn CustomNodeBuilder, SchemaError> {
+    registry
+        .and_then(|r| r.get(node.ty.as_str()))
+        .ok_or_else(|| SchemaError::UnrecognisedCustomNodeType(node.ty.clone()))
+}
+
+/// A builder for a custom, third-party-registered node type.
+///
+/// Implementors receive the [`CustomNode`]'s `ty` string (via [`CustomNode::ty`]) and its
+/// flattened `attributes`, and participate fully in model construction, connector wiring and
+/// metric resolution, in place of the hard `panic!` that previously aborted on any custom node.
+pub trait CustomNodeBuilder: Send + Sync {
+    fn add_to_model(&self, network: &mut pywr_core::network::Network, node: &CustomNode) -> Result<(), SchemaError>;
+
+    fn set_constraints(
+        &self,
+        network: &mut pywr_core::network::Network,
+        node: &CustomNode,
+        tables: &LoadedTableCollection,
+        data_path: Option<&Path>,
+    ) -> Result<(), SchemaError>;
+
+    fn input_connectors<'a>(&self, node: &'a CustomNode) -> Vec<(&'a str, Option<String>)>;
+
+    fn output_connectors<'a>(&self, node: &'a CustomNode, slot: Option<&str>) -> Vec<(&'a str, Option<String>)>;
+
+    fn default_metric(
+        &self,
+        network: &pywr_core::network::Network,
+        node: &CustomNode,
+    ) -> Result<Metric, SchemaError>;
+}
+
+/// A runtime-extensible registry mapping a [`CustomNode`]'s `ty` string to a [`CustomNodeBuilder`],
+/// so downstream crates can register their own node kinds without forking this crate.
+#[derive(Default)]
+pub struct CustomNodeRegistry {
+    builders: HashMap<String, Box<dyn CustomNodeBuilder>>,
+}
+
+impl CustomNodeRegistry {
+    pub fn register(&mut self, type_name: &str, builder: Box<dyn CustomNodeBuilder>) -> Option<Box<dyn CustomNodeBuilder>> {
+        self.builders.insert(type_name.to_string(), builder)
+    }
+
+    pub fn get(&self, type_name: &str) -> Option<&dyn CustomNodeBuilder> {
+        self.builders.get(type_name).map(|b| b.as_ref())
+    }
+}
+
+/// A connection between two top-level schema [`Node`]s, as declared in a model's `edges` list.
+/// `from_slot` identifies which of the source node's named output connectors the edge leaves
+/// from, if the source node has more than one (see [`Node::output_connectors`]).
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Edge {
+    pub from_node: String,
+    pub from_slot: Option<String>,
+    pub to_node: String,
+}
+
+/// Serialize a schema's nodes and edges as a Graphviz DOT document for visualization and
+/// debugging. One vertex is emitted per node, labelled with its name and coloured by
+/// [`Node::node_type`]; one directed edge is emitted per [`Edge`], labelled with `from_slot`
+/// where one is given. Nodes with a [`NodePosition::schematic`] position get a `pos="x,y"`
+/// attribute so tools can render the layout the modeller intended.
+///
+/// An error is returned if either endpoint of an edge is not in `nodes`, or if an endpoint is a
+/// [`CustomNode`] with no builder in `custom_nodes`. A `from_slot` is not checked against core
+/// nodes' connectors: most core nodes ignore the slot, and `RiverSplitWithGauge` panics on an
+/// unrecognised one.
+pub fn to_dot(nodes: &[Node], edges: &[Edge], custom_nodes: Option<&CustomNodeRegistry>) -> Result<String, SchemaError> {
+    let find_node = |name: &str| -> Result<&Node, SchemaError> {
+        nodes
+            .iter()
+            .find(|n| n.name() == name)
+            .ok_or_else(|| SchemaError::NodeNotFound(name.to_string()))
+    };
+
+    let mut dot = String::from("digraph pywr {\n");
+
+    for node in nodes {
+        let mut attrs = vec![
+            format!("label=\"{}\"", node.name()),
+            format!("color=\"{}\"", dot_color_for_node_type(node.node_type())),
+        ];
+
+        if let Some(position) = node.position() {
+            if let Some((x, y)) = position.schematic {
+                attrs.push(format!("pos=\"{x},{y}\""));
+            }
+        }
+
+        dot.push_str(&format!("    \"{}\" [{}];\n", node.name(), attrs.join(", ")));
+    }
+
+    for edge in edges {
+        let from = find_node(&edge.from_node)?;
+        let to = find_node(&edge.to_node)?;
+
+        // Fails only for a custom node with no registered builder; core nodes always succeed.
+        from.output_connectors(edge.from_slot.as_deref(), custom_nodes)?;
+        to.input_connectors(custom_nodes)?;
+
+        match &edge.from_slot {
+            Some(slot) => dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                edge.from_node, edge.to_node, slot
+            )),
+            None => dot.push_str(&format!("    \"{}\" -> \"{}\";\n", edge.from_node, edge.to_node)),
+        }
+    }
+
+    dot.push_str("}\n");
+    Ok(dot)
+}
+
+/// A deterministic colour keyed off a node's type name, used purely to make a rendered DOT
+/// graph easier to scan.
+fn dot_color_for_node_type(node_type: &str) -> &'static str {
+    match node_type {
+        "Input" => "green",
+        "Output" => "red",
+        "Storage" | "AggregatedStorage" => "blue",
+        "River" => "cyan",
+        "VirtualStorage" | "AnnualVirtualStorage" | "MonthlyVirtualStorage" | "RollingVirtualStorage" => "purple",
+        _ => "black",
+    }
+}
+
 impl TryFrom<NodeV1> for Node {
     type Error = ConversionError;
 
@@ -447,9 +599,51 @@ impl TryFrom<Box<CoreNodeV1>> for CoreNode {
             CoreNodeV1::RiverSplit(_) => todo!("Conversion of RiverSplit nodes"),
             CoreNodeV1::MonthlyVirtualStorage(n) => Self::MonthlyVirtualStorage(n.try_into()?),
             CoreNodeV1::SeasonalVirtualStorage(_) => todo!("Conversion of SeasonalVirtualStorage nodes"),
-            CoreNodeV1::RollingVirtualStorage(_) => todo!("Conversion of RollingVirtualStorage nodes"),
+            CoreNodeV1::RollingVirtualStorage(n) => Self::RollingVirtualStorage(n.try_into()?),
         };
 
         Ok(n)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{to_dot, Edge, Node};
+
+    fn node(json: &str) -> Node {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_to_dot_renders_nodes_and_edges() {
+        let nodes = vec![
+            node(r#"{"type": "Input", "name": "supply"}"#),
+            node(r#"{"type": "Output", "name": "demand"}"#),
+        ];
+        let edges = vec![Edge {
+            from_node: "supply".to_string(),
+            from_slot: None,
+            to_node: "demand".to_string(),
+        }];
+
+        let dot = to_dot(&nodes, &edges, None).unwrap();
+
+        assert!(dot.starts_with("digraph pywr {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert!(dot.contains("\"supply\" [label=\"supply\", color=\"green\"];"));
+        assert!(dot.contains("\"demand\" [label=\"demand\", color=\"red\"];"));
+        assert!(dot.contains("\"supply\" -> \"demand\";"));
+    }
+
+    #[test]
+    fn test_to_dot_errors_on_unknown_edge_endpoint() {
+        let nodes = vec![node(r#"{"type": "Input", "name": "supply"}"#)];
+        let edges = vec![Edge {
+            from_node: "supply".to_string(),
+            from_slot: None,
+            to_node: "does-not-exist".to_string(),
+        }];
+
+        assert!(to_dot(&nodes, &edges, None).is_err());
+    }
+}
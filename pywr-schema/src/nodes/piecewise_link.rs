@@ -0,0 +1,323 @@
+use crate::data_tables::LoadedTableCollection;
+use crate::error::{ConversionError, SchemaError};
+use crate::nodes::NodeMeta;
+use crate::parameters::{DynamicFloatValue, TryIntoV2Parameter};
+use pywr_core::aggregated_node::Factors;
+use pywr_core::metric::Metric;
+use pywr_v1_schema::nodes::PiecewiseLinkNode as PiecewiseLinkNodeV1;
+use std::path::Path;
+
+/// A single step of a [`PiecewiseLinkNode`], i.e. one parallel sub-link with its own cost and
+/// maximum flow.
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct PiecewiseLinkStep {
+    /// The maximum flow through this step. `None` implies no limit.
+    pub max_flow: Option<DynamicFloatValue>,
+    /// The cost applied to this step.
+    pub cost: Option<DynamicFloatValue>,
+}
+
+#[doc = svgbobdoc::transform!(
+/// A node that splits flow between an arbitrary number of parallel steps, each with its own
+/// cost and maximum flow, allowing users to build arbitrary piecewise-linear cost/flow curves
+/// (e.g. tiered abstraction licences) without defining a bespoke node.
+///
+/// ```svgbob
+///            <node>.step_0
+///        .------>L ------.
+///   U   |                 |   D
+///  -*---|--->L --------->|-->*- - -
+///       |  <node>.step_1  |
+///        '------>L ------'
+///          <node>.step_n
+/// ```
+)]
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct PiecewiseLinkNode {
+    /// Node metadata
+    #[serde(flatten)]
+    pub meta: NodeMeta,
+    /// The parallel steps that make up this node. Each step is given the sub-name `step_{i}`.
+    pub steps: Vec<PiecewiseLinkStep>,
+    /// Optional factors used to force fixed ratios between the steps via an aggregated node.
+    pub factors: Option<Vec<DynamicFloatValue>>,
+}
+
+impl PiecewiseLinkNode {
+    fn inlet_sub_name() -> Option<&'static str> {
+        Some("inlet")
+    }
+
+    fn outlet_sub_name() -> Option<&'static str> {
+        Some("outlet")
+    }
+
+    fn step_sub_name(i: usize) -> Option<String> {
+        Some(format!("step_{i}"))
+    }
+
+    fn agg_sub_name() -> Option<&'static str> {
+        Some("agg")
+    }
+
+    pub fn add_to_model(&self, model: &mut pywr_core::model::Model) -> Result<(), SchemaError> {
+        let idx_inlet = model.add_link_node(self.meta.name.as_str(), Self::inlet_sub_name())?;
+        let idx_outlet = model.add_link_node(self.meta.name.as_str(), Self::outlet_sub_name())?;
+
+        let mut step_indices = Vec::with_capacity(self.steps.len());
+        for i in 0..self.steps.len() {
+            let idx_step = model.add_link_node(self.meta.name.as_str(), Self::step_sub_name(i).as_deref())?;
+            model.connect_nodes(idx_inlet, idx_step)?;
+            model.connect_nodes(idx_step, idx_outlet)?;
+            step_indices.push(idx_step);
+        }
+
+        if self.factors.is_some() {
+            model.add_aggregated_node(self.meta.name.as_str(), Self::agg_sub_name(), &step_indices, None)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn set_constraints(
+        &self,
+        model: &mut pywr_core::model::Model,
+        tables: &LoadedTableCollection,
+        data_path: Option<&Path>,
+    ) -> Result<(), SchemaError> {
+        for (i, step) in self.steps.iter().enumerate() {
+            let sub_name = Self::step_sub_name(i);
+
+            if let Some(cost) = &step.cost {
+                let value = cost.load(model, tables, data_path)?;
+                model.set_node_cost(self.meta.name.as_str(), sub_name.as_deref(), value.into())?;
+            }
+
+            if let Some(max_flow) = &step.max_flow {
+                let value = max_flow.load(model, tables, data_path)?;
+                model.set_node_max_flow(self.meta.name.as_str(), sub_name.as_deref(), value.into())?;
+            }
+        }
+
+        if let Some(factors) = &self.factors {
+            let factors = factors
+                .iter()
+                .map(|f| f.load(model, tables, data_path))
+                .collect::<Result<_, _>>()?;
+
+            model.set_aggregated_node_factors(
+                self.meta.name.as_str(),
+                Self::agg_sub_name(),
+                Some(Factors::Ratio(factors)),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn input_connectors(&self) -> Vec<(&str, Option<String>)> {
+        vec![(self.meta.name.as_str(), Self::inlet_sub_name().map(|s| s.to_string()))]
+    }
+
+    pub fn output_connectors(&self) -> Vec<(&str, Option<String>)> {
+        vec![(self.meta.name.as_str(), Self::outlet_sub_name().map(|s| s.to_string()))]
+    }
+
+    pub fn default_metric(&self, model: &pywr_core::model::Model) -> Result<Metric, SchemaError> {
+        let idx = model.get_node_index_by_name(self.meta.name.as_str(), Self::outlet_sub_name().as_deref())?;
+        Ok(Metric::NodeOutFlow(idx))
+    }
+}
+
+impl TryFrom<PiecewiseLinkNodeV1> for PiecewiseLinkNode {
+    type Error = ConversionError;
+
+    fn try_from(v1: PiecewiseLinkNodeV1) -> Result<Self, Self::Error> {
+        let meta: NodeMeta = v1.meta.into();
+        let mut unnamed_count = 0;
+
+        let costs = v1.cost.unwrap_or_default();
+        let max_flows = v1.max_flow.unwrap_or_default();
+        let n = costs.len().max(max_flows.len());
+
+        let mut steps = Vec::with_capacity(n);
+        for i in 0..n {
+            let cost = costs
+                .get(i)
+                .cloned()
+                .map(|v| v.try_into_v2_parameter(Some(&meta.name), &mut unnamed_count))
+                .transpose()?;
+            let max_flow = max_flows
+                .get(i)
+                .cloned()
+                .map(|v| v.try_into_v2_parameter(Some(&meta.name), &mut unnamed_count))
+                .transpose()?;
+
+            steps.push(PiecewiseLinkStep { max_flow, cost });
+        }
+
+        Ok(Self {
+            meta,
+            steps,
+            factors: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::PywrModel;
+    use crate::nodes::PiecewiseLinkNode;
+    use ndarray::Array2;
+    use pywr_core::metric::Metric;
+    use pywr_core::recorders::AssertionRecorder;
+    use pywr_core::test_utils::run_all_solvers;
+
+    #[test]
+    fn test_piecewise_link_schema_load() {
+        let data = r#"
+                {
+                  "type": "PiecewiseLink",
+                  "name": "My piecewise link",
+                  "comment": null,
+                  "position": null,
+                  "steps": [
+                    {"max_flow": 10.0, "cost": 0.0},
+                    {"max_flow": 5.0, "cost": 5.0},
+                    {"max_flow": null, "cost": 20.0}
+                  ]
+                }
+            "#;
+
+        let node: PiecewiseLinkNode = serde_json::from_str(data).unwrap();
+
+        assert_eq!(node.meta.name, "My piecewise link");
+        assert_eq!(node.steps.len(), 3);
+    }
+
+    fn model_str() -> &'static str {
+        r#"
+            {
+                "metadata": {
+                    "title": "Piecewise link test",
+                    "description": "Test tiered cost/flow curve",
+                    "minimum_version": "0.1"
+                },
+                "timestepper": {
+                    "start": "2015-01-01",
+                    "end": "2015-12-31",
+                    "timestep": 1
+                },
+                "nodes": [
+                    {
+                        "name": "input1",
+                        "type": "Input",
+                        "flow": 15
+                    },
+                    {
+                        "name": "piecewise1",
+                        "type": "PiecewiseLink",
+                        "steps": [
+                            {"max_flow": 10.0, "cost": 0.0},
+                            {"max_flow": null, "cost": 20.0}
+                        ]
+                    },
+                    {
+                        "name": "demand1",
+                        "type": "Output",
+                        "max_flow": 15.0,
+                        "cost": -10
+                    }
+                ],
+                "edges": [
+                    {
+                        "from_node": "input1",
+                        "to_node": "piecewise1"
+                    },
+                    {
+                        "from_node": "piecewise1",
+                        "to_node": "demand1"
+                    }
+                ]
+            }
+            "#
+    }
+
+    #[test]
+    fn test_model_schema() {
+        let data = model_str();
+        let schema: PywrModel = serde_json::from_str(data).unwrap();
+
+        assert_eq!(schema.nodes.len(), 3);
+        assert_eq!(schema.edges.len(), 2);
+    }
+
+    /// With `factors` set to an equal ratio, the 15 units of inflow must be split 50/50 across
+    /// the two steps even though `step_0`'s cost would otherwise make the solver prefer it.
+    #[test]
+    fn test_model_run_factors_force_ratio() {
+        let data = r#"
+            {
+                "metadata": {
+                    "title": "Piecewise link factors test",
+                    "description": "Test factors force an equal split across steps",
+                    "minimum_version": "0.1"
+                },
+                "timestepper": {
+                    "start": "2015-01-01",
+                    "end": "2015-12-31",
+                    "timestep": 1
+                },
+                "nodes": [
+                    {
+                        "name": "input1",
+                        "type": "Input",
+                        "flow": 15
+                    },
+                    {
+                        "name": "piecewise1",
+                        "type": "PiecewiseLink",
+                        "steps": [
+                            {"max_flow": null, "cost": 0.0},
+                            {"max_flow": null, "cost": 20.0}
+                        ],
+                        "factors": [1.0, 1.0]
+                    },
+                    {
+                        "name": "demand1",
+                        "type": "Output",
+                        "max_flow": 15.0,
+                        "cost": -10
+                    }
+                ],
+                "edges": [
+                    {
+                        "from_node": "input1",
+                        "to_node": "piecewise1"
+                    },
+                    {
+                        "from_node": "piecewise1",
+                        "to_node": "demand1"
+                    }
+                ]
+            }
+            "#;
+
+        let schema: PywrModel = serde_json::from_str(data).unwrap();
+        let (mut model, timestepper) = schema.build_model(None, None).unwrap();
+
+        let scenario_indices = model.get_scenario_indices();
+
+        let idx = model.get_node_by_name("piecewise1", Some("step_0")).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 7.5);
+        let recorder = AssertionRecorder::new("step-0-flow", Metric::NodeOutFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        let idx = model.get_node_by_name("piecewise1", Some("step_1")).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 7.5);
+        let recorder = AssertionRecorder::new("step-1-flow", Metric::NodeOutFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &timestepper);
+    }
+}
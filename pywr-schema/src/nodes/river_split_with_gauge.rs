@@ -0,0 +1,350 @@
+use crate::data_tables::LoadedTableCollection;
+use crate::error::{ConversionError, SchemaError};
+use crate::nodes::NodeMeta;
+use crate::parameters::{DynamicFloatValue, TryIntoV2Parameter};
+use pywr_core::aggregated_node::Factors;
+use pywr_core::metric::Metric;
+use pywr_v1_schema::nodes::RiverSplitWithGaugeNode as RiverSplitWithGaugeNodeV1;
+use std::path::Path;
+
+#[doc = svgbobdoc::transform!(
+/// A river node that abstracts a proportion of flow above a minimum residual flow (MRF) at a gauge.
+///
+/// This node comprises three parallel routes leaving an inlet: a `mrf` route that is given
+/// priority (via `max_flow` and `cost`) to preserve the minimum residual flow, an `abstraction`
+/// route that diverts water away from the river, and a `spill` route that returns any flow not
+/// abstracted back to the river. The `mrf` and `spill` routes re-join at the outlet (the
+/// `river_continuation` slot); the `abstraction` route does not, and is connected onwards through
+/// the `abstraction` slot. The `mrf` route is kept outside of the aggregated node so that the
+/// `factors` only constrain the ratio between the `abstraction` and `spill` routes - i.e.
+/// abstraction is a proportion of whatever flow remains *above* the MRF.
+///
+/// ```svgbob
+///                <node>.mrf
+///            .------>L ------.
+///      U    |                 |      D
+///     -*----|--->L --------->|-->*- - -
+///           |  <node>.spill
+///            '------>L - - - - - - ->
+///             <node>.abstraction
+/// ```
+)]
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct RiverSplitWithGaugeNode {
+    /// Node metadata
+    #[serde(flatten)]
+    pub meta: NodeMeta,
+    /// The minimum residual flow (MRF) that must be preserved at the gauge.
+    pub mrf: Option<DynamicFloatValue>,
+    /// The cost applied to the `mrf` route.
+    pub mrf_cost: Option<DynamicFloatValue>,
+    /// The cost applied to the `abstraction` route.
+    pub cost: Option<DynamicFloatValue>,
+    /// The factors used to force a fixed ratio between the `abstraction` and `spill` routes.
+    pub factors: Option<Vec<DynamicFloatValue>>,
+}
+
+impl RiverSplitWithGaugeNode {
+    fn inlet_sub_name() -> Option<&'static str> {
+        Some("inlet")
+    }
+
+    fn mrf_sub_name() -> Option<&'static str> {
+        Some("mrf")
+    }
+
+    fn abstraction_sub_name() -> Option<&'static str> {
+        Some("abstraction")
+    }
+
+    fn spill_sub_name() -> Option<&'static str> {
+        Some("spill")
+    }
+
+    fn outlet_sub_name() -> Option<&'static str> {
+        Some("outlet")
+    }
+
+    fn agg_sub_name() -> Option<&'static str> {
+        Some("agg")
+    }
+
+    pub fn add_to_model(&self, model: &mut pywr_core::model::Model) -> Result<(), SchemaError> {
+        let idx_inlet = model.add_link_node(self.meta.name.as_str(), Self::inlet_sub_name())?;
+        let idx_mrf = model.add_link_node(self.meta.name.as_str(), Self::mrf_sub_name())?;
+        let idx_abstraction = model.add_link_node(self.meta.name.as_str(), Self::abstraction_sub_name())?;
+        let idx_spill = model.add_link_node(self.meta.name.as_str(), Self::spill_sub_name())?;
+        let idx_outlet = model.add_link_node(self.meta.name.as_str(), Self::outlet_sub_name())?;
+
+        model.connect_nodes(idx_inlet, idx_mrf)?;
+        model.connect_nodes(idx_inlet, idx_abstraction)?;
+        model.connect_nodes(idx_inlet, idx_spill)?;
+
+        // The MRF route re-joins the river directly; it is *not* part of the aggregated node
+        // below, so the abstraction/spill ratio only applies to flow above the MRF.
+        model.connect_nodes(idx_mrf, idx_outlet)?;
+        model.connect_nodes(idx_spill, idx_outlet)?;
+
+        // This aggregated node will contain the factors that force the abstraction/spill ratio.
+        model.add_aggregated_node(
+            self.meta.name.as_str(),
+            Self::agg_sub_name(),
+            &[idx_abstraction, idx_spill],
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn set_constraints(
+        &self,
+        model: &mut pywr_core::model::Model,
+        tables: &LoadedTableCollection,
+        data_path: Option<&Path>,
+    ) -> Result<(), SchemaError> {
+        if let Some(mrf) = &self.mrf {
+            let value = mrf.load(model, tables, data_path)?;
+            model.set_node_max_flow(self.meta.name.as_str(), Self::mrf_sub_name(), value.into())?;
+        }
+
+        if let Some(mrf_cost) = &self.mrf_cost {
+            let value = mrf_cost.load(model, tables, data_path)?;
+            model.set_node_cost(self.meta.name.as_str(), Self::mrf_sub_name(), value.into())?;
+        }
+
+        if let Some(cost) = &self.cost {
+            let value = cost.load(model, tables, data_path)?;
+            model.set_node_cost(self.meta.name.as_str(), Self::abstraction_sub_name(), value.into())?;
+        }
+
+        if let Some(factors) = &self.factors {
+            let factors = factors
+                .iter()
+                .map(|f| f.load(model, tables, data_path))
+                .collect::<Result<_, _>>()?;
+
+            model.set_aggregated_node_factors(
+                self.meta.name.as_str(),
+                Self::agg_sub_name(),
+                Some(Factors::Ratio(factors)),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn input_connectors(&self) -> Vec<(&str, Option<String>)> {
+        vec![(self.meta.name.as_str(), Self::inlet_sub_name().map(|s| s.to_string()))]
+    }
+
+    pub fn output_connectors(&self, slot: Option<&str>) -> Vec<(&str, Option<String>)> {
+        match slot {
+            Some("abstraction") => vec![(
+                self.meta.name.as_str(),
+                Self::abstraction_sub_name().map(|s| s.to_string()),
+            )],
+            None | Some("river_continuation") => vec![(
+                self.meta.name.as_str(),
+                Self::outlet_sub_name().map(|s| s.to_string()),
+            )],
+            Some(slot) => panic!(
+                "Unrecognised output slot '{}' for RiverSplitWithGauge node '{}'",
+                slot,
+                self.meta.name.as_str()
+            ),
+        }
+    }
+
+    pub fn default_metric(&self, model: &pywr_core::model::Model) -> Result<Metric, SchemaError> {
+        let idx = model.get_node_index_by_name(self.meta.name.as_str(), Self::outlet_sub_name().as_deref())?;
+        Ok(Metric::NodeOutFlow(idx))
+    }
+}
+
+impl TryFrom<RiverSplitWithGaugeNodeV1> for RiverSplitWithGaugeNode {
+    type Error = ConversionError;
+
+    fn try_from(v1: RiverSplitWithGaugeNodeV1) -> Result<Self, Self::Error> {
+        let meta: NodeMeta = v1.meta.into();
+        let mut unnamed_count = 0;
+
+        let mrf = v1
+            .mrf
+            .map(|v| v.try_into_v2_parameter(Some(&meta.name), &mut unnamed_count))
+            .transpose()?;
+
+        let mrf_cost = v1
+            .mrf_cost
+            .map(|v| v.try_into_v2_parameter(Some(&meta.name), &mut unnamed_count))
+            .transpose()?;
+
+        let cost = v1
+            .cost
+            .map(|v| v.try_into_v2_parameter(Some(&meta.name), &mut unnamed_count))
+            .transpose()?;
+
+        let factors = v1
+            .factors
+            .map(|values| {
+                values
+                    .into_iter()
+                    .map(|v| v.try_into_v2_parameter(Some(&meta.name), &mut unnamed_count))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+
+        Ok(Self {
+            meta,
+            mrf,
+            mrf_cost,
+            cost,
+            factors,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::PywrModel;
+    use crate::nodes::RiverSplitWithGaugeNode;
+    use ndarray::Array2;
+    use pywr_core::metric::Metric;
+    use pywr_core::recorders::AssertionRecorder;
+    use pywr_core::test_utils::run_all_solvers;
+
+    #[test]
+    fn test_river_split_with_gauge_schema_load() {
+        let data = r#"
+                {
+                  "type": "RiverSplitWithGauge",
+                  "name": "My river split",
+                  "comment": null,
+                  "position": null,
+                  "mrf": 30.0,
+                  "mrf_cost": -1000.0,
+                  "cost": 1.0,
+                  "factors": [0.5, 0.5]
+                }
+            "#;
+
+        let node: RiverSplitWithGaugeNode = serde_json::from_str(data).unwrap();
+
+        assert_eq!(node.meta.name, "My river split");
+    }
+
+    fn model_str() -> &'static str {
+        r#"
+            {
+                "metadata": {
+                    "title": "River split test",
+                    "description": "Test abstraction above an MRF",
+                    "minimum_version": "0.1"
+                },
+                "timestepper": {
+                    "start": "2015-01-01",
+                    "end": "2015-12-31",
+                    "timestep": 1
+                },
+                "nodes": [
+                    {
+                        "name": "catchment1",
+                        "type": "Catchment",
+                        "flow": 15
+                    },
+                    {
+                        "name": "split1",
+                        "type": "RiverSplitWithGauge",
+                        "mrf": 10.0,
+                        "mrf_cost": -1000.0,
+                        "cost": 0.0,
+                        "factors": [0.5, 0.5]
+                    },
+                    {
+                        "name": "abstraction-demand",
+                        "type": "Output",
+                        "max_flow": 100.0,
+                        "cost": -10
+                    },
+                    {
+                        "name": "river-gauge",
+                        "type": "Output",
+                        "max_flow": 100.0,
+                        "cost": 0.0
+                    }
+                ],
+                "edges": [
+                    {
+                        "from_node": "catchment1",
+                        "to_node": "split1"
+                    },
+                    {
+                        "from_node": "split1",
+                        "to_node": "abstraction-demand",
+                        "from_slot": "abstraction"
+                    },
+                    {
+                        "from_node": "split1",
+                        "to_node": "river-gauge",
+                        "from_slot": "river_continuation"
+                    }
+                ]
+            }
+            "#
+    }
+
+    #[test]
+    fn test_model_schema() {
+        let data = model_str();
+        let schema: PywrModel = serde_json::from_str(data).unwrap();
+
+        assert_eq!(schema.nodes.len(), 4);
+        assert_eq!(schema.edges.len(), 3);
+    }
+
+    /// Catchment flow (15) exceeds the MRF (10), so the MRF route is fully satisfied and the
+    /// remaining 5 is split 50/50 between abstraction and the river continuation.
+    #[test]
+    fn test_model_run_mrf_binding() {
+        let data = model_str();
+        let schema: PywrModel = serde_json::from_str(data).unwrap();
+        let (mut model, timestepper) = schema.build_model(None, None).unwrap();
+
+        let scenario_indices = model.get_scenario_indices();
+
+        let idx = model.get_node_by_name("abstraction-demand", None).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 2.5);
+        let recorder = AssertionRecorder::new("abstraction-flow", Metric::NodeInFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        // The river gauge receives the MRF (10) plus the spill (2.5).
+        let idx = model.get_node_by_name("river-gauge", None).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 12.5);
+        let recorder = AssertionRecorder::new("river-gauge-flow", Metric::NodeInFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &timestepper);
+    }
+
+    /// Catchment flow (8) is below the MRF (10), so the MRF route passes through everything
+    /// available and nothing is left for abstraction or spill.
+    #[test]
+    fn test_model_run_mrf_non_binding() {
+        let data = model_str().replace(r#""flow": 15"#, r#""flow": 8"#);
+        let schema: PywrModel = serde_json::from_str(&data).unwrap();
+        let (mut model, timestepper) = schema.build_model(None, None).unwrap();
+
+        let scenario_indices = model.get_scenario_indices();
+
+        let idx = model.get_node_by_name("abstraction-demand", None).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 0.0);
+        let recorder = AssertionRecorder::new("abstraction-flow", Metric::NodeInFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        let idx = model.get_node_by_name("river-gauge", None).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 8.0);
+        let recorder = AssertionRecorder::new("river-gauge-flow", Metric::NodeInFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &timestepper);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::data_tables::LoadedTableCollection;
+use crate::error::{ConversionError, SchemaError};
+use crate::model::PywrMultiNetworkTransfer;
+use crate::nodes::NodeMeta;
+use crate::parameters::{DynamicFloatValue, TryIntoV2Parameter};
+use pywr_core::metric::Metric;
+use pywr_core::models::ModelDomain;
+use pywr_core::node::{ConstraintValue, StorageInitialVolume};
+use pywr_core::virtual_storage::VirtualStorageReset;
+use pywr_v1_schema::nodes::RollingVirtualStorageNode as RollingVirtualStorageNodeV1;
+use std::path::Path;
+
+/// The trailing window over which a [`RollingVirtualStorageNode`] tracks cumulative net flow.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy)]
+pub enum RollingWindow {
+    Timesteps(usize),
+    Days(usize),
+}
+
+/// A virtual storage node whose available volume is bounded not by a fixed licence that is
+/// consumed over the whole run, but by the cumulative net flow through its contributing nodes
+/// over a trailing window of the last `window` timesteps (or days). As each timestep's flow
+/// falls out of the back of the window it is automatically credited back, giving a rolling
+/// licence rather than an annual or all-time one.
+#[derive(serde::Deserialize, serde::Serialize, Clone)]
+pub struct RollingVirtualStorageNode {
+    #[serde(flatten)]
+    pub meta: NodeMeta,
+    pub nodes: Vec<String>,
+    pub factors: Option<Vec<f64>>,
+    pub window: RollingWindow,
+    pub max_volume: Option<DynamicFloatValue>,
+    pub cost: Option<DynamicFloatValue>,
+}
+
+impl RollingVirtualStorageNode {
+    pub fn add_to_model(
+        &self,
+        network: &mut pywr_core::network::Network,
+        domain: &ModelDomain,
+        tables: &LoadedTableCollection,
+        data_path: Option<&Path>,
+        inter_network_transfers: &[PywrMultiNetworkTransfer],
+    ) -> Result<(), SchemaError> {
+        // A rolling licence always starts fully available; there is no meaningful "initial
+        // volume" to configure separately from the window itself.
+        let initial_volume = StorageInitialVolume::Proportional(1.0);
+
+        let cost = match &self.cost {
+            Some(v) => v
+                .load(network, domain, tables, data_path, inter_network_transfers)?
+                .into(),
+            None => ConstraintValue::Scalar(0.0),
+        };
+
+        let max_volume = match &self.max_volume {
+            Some(v) => v
+                .load(network, domain, tables, data_path, inter_network_transfers)?
+                .into(),
+            None => ConstraintValue::None,
+        };
+
+        let node_idxs = self
+            .nodes
+            .iter()
+            .map(|name| network.get_node_index_by_name(name.as_str(), None))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let timesteps = match self.window {
+            RollingWindow::Timesteps(n) => n,
+            RollingWindow::Days(days) => domain.timestepper().timesteps_in_days(days)?,
+        };
+
+        // The window advances exactly once per timestep; the reset itself (crediting the oldest
+        // entry back as it falls out of the window) is handled by the rolling window's own
+        // bookkeeping rather than by a calendar reset point.
+        let reset = VirtualStorageReset::Rolling { timesteps };
+
+        network.add_virtual_storage_node(
+            self.meta.name.as_str(),
+            None,
+            &node_idxs,
+            self.factors.as_deref(),
+            initial_volume,
+            ConstraintValue::Scalar(0.0),
+            max_volume,
+            reset,
+            None,
+            cost,
+        )?;
+        Ok(())
+    }
+
+    pub fn input_connectors(&self) -> Vec<(&str, Option<String>)> {
+        vec![]
+    }
+
+    pub fn output_connectors(&self) -> Vec<(&str, Option<String>)> {
+        vec![]
+    }
+
+    pub fn default_metric(&self, network: &pywr_core::network::Network) -> Result<Metric, SchemaError> {
+        let idx = network.get_virtual_storage_node_index_by_name(self.meta.name.as_str(), None)?;
+        Ok(Metric::VirtualStorageVolume(idx))
+    }
+}
+
+impl TryFrom<RollingVirtualStorageNodeV1> for RollingVirtualStorageNode {
+    type Error = ConversionError;
+
+    fn try_from(v1: RollingVirtualStorageNodeV1) -> Result<Self, Self::Error> {
+        let meta: NodeMeta = v1.meta.into();
+        let mut unnamed_count = 0;
+
+        let cost = v1
+            .cost
+            .map(|v| v.try_into_v2_parameter(Some(&meta.name), &mut unnamed_count))
+            .transpose()?;
+
+        let max_volume = v1
+            .max_volume
+            .map(|v| v.try_into_v2_parameter(Some(&meta.name), &mut unnamed_count))
+            .transpose()?;
+
+        let window = match v1.days {
+            Some(days) => RollingWindow::Days(days),
+            None => RollingWindow::Timesteps(v1.timesteps.unwrap_or(1)),
+        };
+
+        Ok(Self {
+            meta,
+            nodes: v1.nodes,
+            factors: v1.factors,
+            window,
+            max_volume,
+            cost,
+        })
+    }
+}
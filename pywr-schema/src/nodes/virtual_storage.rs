@@ -1,7 +1,7 @@
 use crate::data_tables::LoadedTableCollection;
 use crate::error::{ConversionError, SchemaError};
 use crate::model::PywrMultiNetworkTransfer;
-use crate::nodes::NodeMeta;
+use crate::nodes::{NodeMeta, RollingWindow};
 use crate::parameters::{DynamicFloatValue, TryIntoV2Parameter};
 use pywr_core::metric::Metric;
 use pywr_core::models::ModelDomain;
@@ -10,6 +10,39 @@ use pywr_core::virtual_storage::VirtualStorageReset;
 use pywr_v1_schema::nodes::VirtualStorageNode as VirtualStorageNodeV1;
 use std::path::Path;
 
+/// When a [`VirtualStorageNode`]'s accumulated volume is reset back to its initial state.
+///
+/// This mirrors [`VirtualStorageReset`], except [`Self::Rolling`] is expressed in the same
+/// [`RollingWindow`] terms (timesteps or days) used by [`RollingVirtualStorageNode`](super::RollingVirtualStorageNode),
+/// since the concrete number of timesteps in a rolling window depends on the model's own timestep.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default)]
+#[serde(tag = "type")]
+pub enum VirtualStorageResetSchema {
+    /// Never reset; the volume accumulates for the life of the model. This is the default.
+    #[default]
+    Never,
+    /// Reset annually on the given day and month.
+    DayOfYear { day: u8, month: u8 },
+    /// Reset on a trailing rolling window, as opposed to a fixed calendar date.
+    Rolling { window: RollingWindow },
+}
+
+impl VirtualStorageResetSchema {
+    fn into_core(self, domain: &ModelDomain) -> Result<VirtualStorageReset, SchemaError> {
+        match self {
+            Self::Never => Ok(VirtualStorageReset::Never),
+            Self::DayOfYear { day, month } => Ok(VirtualStorageReset::DayOfYear { day, month }),
+            Self::Rolling { window } => {
+                let timesteps = match window {
+                    RollingWindow::Timesteps(n) => n,
+                    RollingWindow::Days(days) => domain.timestepper().timesteps_in_days(days)?,
+                };
+                Ok(VirtualStorageReset::Rolling { timesteps })
+            }
+        }
+    }
+}
+
 #[derive(serde::Deserialize, serde::Serialize, Clone, Default)]
 pub struct VirtualStorageNode {
     #[serde(flatten)]
@@ -21,6 +54,9 @@ pub struct VirtualStorageNode {
     pub cost: Option<DynamicFloatValue>,
     pub initial_volume: Option<f64>,
     pub initial_volume_pc: Option<f64>,
+    /// When the accumulated volume resets. Defaults to [`VirtualStorageResetSchema::Never`].
+    #[serde(default)]
+    pub reset: VirtualStorageResetSchema,
 }
 
 impl VirtualStorageNode {
@@ -67,8 +103,7 @@ impl VirtualStorageNode {
             .map(|name| network.get_node_index_by_name(name.as_str(), None))
             .collect::<Result<Vec<_>, _>>()?;
 
-        // Standard virtual storage node never resets.
-        let reset = VirtualStorageReset::Never;
+        let reset = self.reset.into_core(domain)?;
 
         network.add_virtual_storage_node(
             self.meta.name.as_str(),
@@ -121,6 +156,14 @@ impl TryFrom<VirtualStorageNodeV1> for VirtualStorageNode {
             .map(|v| v.try_into_v2_parameter(Some(&meta.name), &mut unnamed_count))
             .transpose()?;
 
+        // A V1 virtual storage only expresses a calendar reset via a day/month pair. V1 rolling
+        // licences are a separate node type, converted to `RollingVirtualStorageNode` instead, so
+        // a V1 virtual storage never converts to a `Rolling` reset.
+        let reset = match (v1.reset_day, v1.reset_month) {
+            (Some(day), Some(month)) => VirtualStorageResetSchema::DayOfYear { day, month },
+            _ => VirtualStorageResetSchema::Never,
+        };
+
         let n = Self {
             meta,
             nodes: v1.nodes,
@@ -130,6 +173,7 @@ impl TryFrom<VirtualStorageNodeV1> for VirtualStorageNode {
             cost,
             initial_volume: v1.initial_volume,
             initial_volume_pc: v1.initial_volume_pc,
+            reset,
         };
         Ok(n)
     }
@@ -7,6 +7,28 @@ use pywr_core::aggregated_node::Factors;
 use pywr_core::metric::Metric;
 use std::path::Path;
 
+/// Whether a [`WaterTreatmentWorks`]'s `loss_factor` is a proportion of the net or the gross flow.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, PartialEq)]
+pub enum LossFactorBasis {
+    /// The loss factor is a proportion of *net* flow; gross flow becomes `(1 + loss_factor) * net`.
+    #[default]
+    Net,
+    /// The loss factor is a proportion of *gross* flow; net flow becomes `(1 - loss_factor) * gross`.
+    Gross,
+}
+
+/// How the loss coupling between the `net` and `loss` nodes is enforced.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Default, PartialEq)]
+pub enum LossFactorConstraint {
+    /// The loss must equal exactly `loss_factor * net` (or equivalent for the gross basis).
+    #[default]
+    Equal,
+    /// The loss may be no more than `loss_factor * net`; it is an upper bound rather than a fixed fraction.
+    LessThanOrEqual,
+    /// The loss must be at least `loss_factor * net`.
+    GreaterThanOrEqual,
+}
+
 #[doc = svgbobdoc::transform!(
 /// A node used to represent a water treatment works (WTW) with optional losses.
 ///
@@ -52,6 +74,18 @@ pub struct WaterTreatmentWorks {
     pub soft_min_flow_cost: Option<DynamicFloatValue>,
     /// The cost applied to the `net` flow node.
     pub cost: Option<DynamicFloatValue>,
+    /// Whether `loss_factor` is expressed as a proportion of net or gross flow. Defaults to [`LossFactorBasis::Net`].
+    #[serde(default)]
+    pub loss_factor_basis: LossFactorBasis,
+    /// How the loss coupling is enforced. Defaults to [`LossFactorConstraint::Equal`].
+    #[serde(default)]
+    pub loss_factor_constraint: LossFactorConstraint,
+    /// When `true`, [`Self::default_metric`] reports the cumulative (trapezoidally-integrated)
+    /// treated flow total via [`Metric::IntegratedNodeOutFlow`], rather than the instantaneous
+    /// flow through the `net` node. Defaults to `false` (instantaneous flow), which is what every
+    /// existing model built against this node expects.
+    #[serde(default)]
+    pub cumulative_flow_metric: bool,
 }
 
 impl WaterTreatmentWorks {
@@ -153,9 +187,32 @@ impl WaterTreatmentWorks {
             };
 
             if let Some(lf) = lf {
-                // Set the factors for the loss
-                // TODO allow for configuring as proportion of gross.
-                let factors = Factors::Ratio(vec![Metric::Constant(1.0), lf]);
+                // The ratio vector depends on whether the loss factor is a proportion of net
+                // or gross flow: net = [1, lf] (gross = (1 + lf) * net), gross = [1 - lf, lf]
+                // (net = (1 - lf) * gross).
+                let ratio = match self.loss_factor_basis {
+                    LossFactorBasis::Net => vec![Metric::Constant(1.0), lf],
+                    LossFactorBasis::Gross => {
+                        // `1 - lf`, built from the generic Metric aggregation primitives so it
+                        // works whether `lf` is a constant or itself a dynamic metric.
+                        let negative_lf = Metric::Aggregated {
+                            metrics: vec![Metric::Constant(-1.0), lf.clone()],
+                            agg_func: pywr_core::metric::AggFunc::Product,
+                        };
+                        let one_minus_lf = Metric::Aggregated {
+                            metrics: vec![Metric::Constant(1.0), negative_lf],
+                            agg_func: pywr_core::metric::AggFunc::Sum,
+                        };
+                        vec![one_minus_lf, lf]
+                    }
+                };
+
+                // Set the factors for the loss, either as a fixed ratio or as an upper/lower bound.
+                let factors = match self.loss_factor_constraint {
+                    LossFactorConstraint::Equal => Factors::Ratio(ratio),
+                    LossFactorConstraint::LessThanOrEqual => Factors::RatioLEQ(ratio),
+                    LossFactorConstraint::GreaterThanOrEqual => Factors::RatioGEQ(ratio),
+                };
                 model.set_aggregated_node_factors(self.meta.name.as_str(), Self::agg_sub_name(), Some(factors))?;
             }
         }
@@ -189,7 +246,11 @@ impl WaterTreatmentWorks {
 
     pub fn default_metric(&self, model: &pywr_core::model::Model) -> Result<Metric, SchemaError> {
         let idx = model.get_node_index_by_name(self.meta.name.as_str(), Self::net_sub_name().as_deref())?;
-        Ok(Metric::NodeOutFlow(idx))
+        if self.cumulative_flow_metric {
+            Ok(Metric::IntegratedNodeOutFlow(idx))
+        } else {
+            Ok(Metric::NodeOutFlow(idx))
+        }
     }
 }
 
@@ -331,4 +392,164 @@ mod tests {
         // Test all solvers
         run_all_solvers(&model, &timestepper);
     }
+
+    #[test]
+    fn test_cumulative_flow_metric_default() {
+        // With `cumulative_flow_metric` left at its default (`false`), `default_metric` still
+        // reports the instantaneous flow, so existing models are unaffected.
+        let data = model_str();
+        let schema: PywrModel = serde_json::from_str(data).unwrap();
+        let (model, _) = schema.build_model(None, None).unwrap();
+
+        let node = schema
+            .nodes
+            .iter()
+            .find_map(|n| match n {
+                crate::nodes::Node::Core(crate::nodes::CoreNode::WaterTreatmentWorks(n)) if n.meta.name == "wtw1" => Some(n),
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(!node.cumulative_flow_metric);
+        let idx = model.get_node_index_by_name("wtw1", WaterTreatmentWorks::net_sub_name().as_deref()).unwrap();
+        assert_eq!(node.default_metric(&model).unwrap(), Metric::NodeOutFlow(idx));
+    }
+
+    #[test]
+    fn test_model_run_cumulative_flow_metric() {
+        let data = model_str().replace(
+            r#""loss_factor": 0.1"#,
+            r#""loss_factor": 0.1, "cumulative_flow_metric": true"#,
+        );
+        let schema: PywrModel = serde_json::from_str(&data).unwrap();
+        let (mut model, timestepper) = schema.build_model(None, None).unwrap();
+
+        let node = schema
+            .nodes
+            .iter()
+            .find_map(|n| match n {
+                crate::nodes::Node::Core(crate::nodes::CoreNode::WaterTreatmentWorks(n)) if n.meta.name == "wtw1" => Some(n),
+                _ => None,
+            })
+            .unwrap();
+
+        assert!(node.cumulative_flow_metric);
+        let metric = node.default_metric(&model).unwrap();
+        let idx = model.get_node_index_by_name("wtw1", WaterTreatmentWorks::net_sub_name().as_deref()).unwrap();
+        assert_eq!(metric, Metric::IntegratedNodeOutFlow(idx));
+
+        // The `net` node carries a constant 10.0 flow every timestep (see `model_str`), so its
+        // trapezoidally-integrated total after n timesteps (0-indexed) is 10.0 * (n + 0.5): the
+        // first timestep only integrates the half-trapezoid from a zero starting flow, and every
+        // timestep after that adds a full 10.0 (both ends of the trapezoid at 10.0).
+        let n = timestepper.timesteps().len();
+        let scenario_indices = model.get_scenario_indices();
+        let mut expected = Array2::zeros((n, scenario_indices.len()));
+        for (i, mut row) in expected.outer_iter_mut().enumerate() {
+            row.fill(10.0 * (i as f64 + 0.5));
+        }
+
+        let recorder = AssertionRecorder::new("wtw1-cumulative-flow", metric, expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &timestepper);
+    }
+
+    #[test]
+    fn test_model_run_gross_basis() {
+        // With `loss_factor_basis: Gross`, `loss_factor` is a proportion of the *gross* (net +
+        // loss) flow rather than the net flow, so the net:loss ratio becomes
+        // `(1 - lf) : lf` instead of `1 : lf`. With `max_flow` still capping `net` at 10.0 and
+        // `lf` = 0.1, `loss` = 10.0 * 0.1 / 0.9 = 1.1111..., so `input1` must supply
+        // 10.0 + 1.1111... = 11.1111... to satisfy both the net cap and the ratio.
+        let data = model_str().replace(
+            r#""loss_factor": 0.1"#,
+            r#""loss_factor": 0.1, "loss_factor_basis": "Gross""#,
+        );
+        let schema: PywrModel = serde_json::from_str(&data).unwrap();
+        let (mut model, timestepper) = schema.build_model(None, None).unwrap();
+
+        let scenario_indices = model.get_scenario_indices();
+
+        let idx = model.get_node_by_name("input1", None).unwrap().index();
+        let expected = Array2::from_elem(
+            (timestepper.timesteps().len(), scenario_indices.len()),
+            10.0 + 10.0 * 0.1 / 0.9,
+        );
+        let recorder = AssertionRecorder::new("input-flow", Metric::NodeOutFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        let idx = model.get_node_by_name("demand1", None).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 10.0);
+        let recorder = AssertionRecorder::new("demand-flow", Metric::NodeInFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &timestepper);
+    }
+
+    #[test]
+    fn test_model_run_loss_factor_constraint_leq() {
+        // With `loss_factor_constraint: LessThanOrEqual`, `loss` is only an upper bound
+        // (`loss <= loss_factor * net`) rather than a fixed ratio, so the solver is otherwise
+        // free to send less flow through `loss`. Giving `loss` a negative cost forces the
+        // solver to maximise it, which pushes it all the way up to the bound, reproducing the
+        // same `net` = 10.0 / `loss` = 1.0 / `input1` = 11.0 result as the default `Equal`
+        // constraint in `test_model_run`.
+        let data = model_str().replace(
+            r#""loss_factor": 0.1"#,
+            r#""loss_factor": 0.1, "loss_factor_constraint": "LessThanOrEqual""#,
+        );
+        let schema: PywrModel = serde_json::from_str(&data).unwrap();
+        let (mut model, timestepper) = schema.build_model(None, None).unwrap();
+
+        model
+            .set_node_cost("wtw1", WaterTreatmentWorks::loss_sub_name(), Metric::Constant(-1.0).into())
+            .unwrap();
+
+        let scenario_indices = model.get_scenario_indices();
+
+        let idx = model.get_node_by_name("input1", None).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 11.0);
+        let recorder = AssertionRecorder::new("input-flow", Metric::NodeOutFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        let idx = model.get_node_by_name("demand1", None).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 10.0);
+        let recorder = AssertionRecorder::new("demand-flow", Metric::NodeInFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &timestepper);
+    }
+
+    #[test]
+    fn test_model_run_loss_factor_constraint_geq() {
+        // With `loss_factor_constraint: GreaterThanOrEqual`, `loss` is only a lower bound
+        // (`loss >= loss_factor * net`). Giving `loss` a positive cost forces the solver to
+        // minimise it, which pulls it back down to the bound, again reproducing the same
+        // `net` = 10.0 / `loss` = 1.0 / `input1` = 11.0 result as `Equal`.
+        let data = model_str().replace(
+            r#""loss_factor": 0.1"#,
+            r#""loss_factor": 0.1, "loss_factor_constraint": "GreaterThanOrEqual""#,
+        );
+        let schema: PywrModel = serde_json::from_str(&data).unwrap();
+        let (mut model, timestepper) = schema.build_model(None, None).unwrap();
+
+        model
+            .set_node_cost("wtw1", WaterTreatmentWorks::loss_sub_name(), Metric::Constant(1.0).into())
+            .unwrap();
+
+        let scenario_indices = model.get_scenario_indices();
+
+        let idx = model.get_node_by_name("input1", None).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 11.0);
+        let recorder = AssertionRecorder::new("input-flow", Metric::NodeOutFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        let idx = model.get_node_by_name("demand1", None).unwrap().index();
+        let expected = Array2::from_elem((timestepper.timesteps().len(), scenario_indices.len()), 10.0);
+        let recorder = AssertionRecorder::new("demand-flow", Metric::NodeInFlow(idx), expected, None, None);
+        model.add_recorder(Box::new(recorder)).unwrap();
+
+        run_all_solvers(&model, &timestepper);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use crate::SchemaError;
+use polars::prelude::*;
+use pywr_core::models::ModelDomain;
+use time::Date;
+
+/// The native sampling frequency of a loaded [`Timeseries`](crate::timeseries::Timeseries), used
+/// to decide whether it needs to be upsampled or downsampled onto the model's time index.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Annual,
+}
+
+/// How a finer-grained series is reduced onto a coarser model timestep (downsampling).
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy)]
+pub enum AggregationFunction {
+    Mean,
+    Sum,
+    Min,
+    Max,
+}
+
+impl AggregationFunction {
+    fn reduce(&self, values: &[f64]) -> f64 {
+        match self {
+            Self::Mean => values.iter().sum::<f64>() / values.len() as f64,
+            Self::Sum => values.iter().sum(),
+            Self::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// How a coarser-grained series is expanded onto a finer model timestep (upsampling).
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, Default)]
+pub enum UpsampleMethod {
+    /// Hold the most recent known value until the next source point.
+    #[default]
+    ForwardFill,
+    /// Linearly interpolate between the surrounding source points.
+    Linear,
+}
+
+/// Reindex and resample `df` from its native `frequency` onto the model's own time index.
+///
+/// The source `DataFrame` must contain a date column named `date_column`; every other column is
+/// resampled independently. If `frequency` is finer than the model's timestep the series is
+/// downsampled using `aggregation`; if it is coarser the series is upsampled using
+/// `upsample_method`. The result is clipped/padded to the domain's start and end dates; an error
+/// is returned if the resampled series still does not cover the full simulation period.
+pub fn align_and_resample(
+    name: &str,
+    df: DataFrame,
+    date_column: &str,
+    frequency: Frequency,
+    aggregation: AggregationFunction,
+    upsample_method: UpsampleMethod,
+    domain: &ModelDomain,
+) -> Result<DataFrame, SchemaError> {
+    let source_dates = read_dates(&df, date_column)?;
+    let target_dates = domain.time_index().dates();
+
+    let model_step_is_finer = target_dates.len() >= source_dates.len() && frequency != Frequency::Daily;
+
+    let mut out = DataFrame::new(vec![Series::new(
+        date_column,
+        target_dates.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+    )])?;
+
+    for column in df.get_column_names() {
+        if column == date_column {
+            continue;
+        }
+        let series = df.column(column)?.cast(&DataType::Float64)?;
+        let values: Vec<f64> = series.f64()?.into_no_null_iter().collect();
+
+        let resampled = if model_step_is_finer {
+            upsample(&source_dates, &values, &target_dates, upsample_method)
+        } else {
+            downsample(&source_dates, &values, &target_dates, aggregation)
+        };
+
+        out.with_column(Series::new(column, resampled))?;
+    }
+
+    let domain_start = target_dates
+        .first()
+        .ok_or_else(|| SchemaError::Timeseries(format!("Model domain for `{name}` has no timesteps.")))?;
+    let domain_end = target_dates
+        .last()
+        .ok_or_else(|| SchemaError::Timeseries(format!("Model domain for `{name}` has no timesteps.")))?;
+
+    if source_dates.first().map_or(true, |d| d > domain_start) || source_dates.last().map_or(true, |d| d < domain_end)
+    {
+        return Err(SchemaError::TimeseriesDoesNotCoverSimulationPeriod(name.to_string()));
+    }
+
+    Ok(out)
+}
+
+fn read_dates(df: &DataFrame, date_column: &str) -> Result<Vec<Date>, SchemaError> {
+    df.column(date_column)?
+        .utf8()?
+        .into_no_null_iter()
+        .map(|s| {
+            Date::parse(s, &time::format_description::well_known::Iso8601::DATE)
+                .map_err(|e| SchemaError::Timeseries(format!("Failed to parse date `{s}`: {e}")))
+        })
+        .collect()
+}
+
+/// Downsample a finer-grained series onto `target_dates` by aggregating all source values that
+/// fall within each target period.
+fn downsample(source_dates: &[Date], values: &[f64], target_dates: &[Date], aggregation: AggregationFunction) -> Vec<f64> {
+    target_dates
+        .iter()
+        .enumerate()
+        .map(|(i, &target)| {
+            // The last period ends one model timestep after it starts, so source data beyond the
+            // domain's end date is not folded into it.
+            let next = target_dates
+                .get(i + 1)
+                .copied()
+                .or_else(|| i.checked_sub(1).map(|prev| target + (target - target_dates[prev])));
+            let bucket: Vec<f64> = source_dates
+                .iter()
+                .zip(values)
+                .filter(|(&d, _)| d >= target && next.map_or(true, |next| d < next))
+                .map(|(_, &v)| v)
+                .collect();
+
+            if bucket.is_empty() {
+                f64::NAN
+            } else {
+                aggregation.reduce(&bucket)
+            }
+        })
+        .collect()
+}
+
+/// Upsample a coarser-grained series onto `target_dates`, holding the most recent source value
+/// (forward-fill) or interpolating linearly between the surrounding source points.
+fn upsample(source_dates: &[Date], values: &[f64], target_dates: &[Date], method: UpsampleMethod) -> Vec<f64> {
+    target_dates
+        .iter()
+        .map(|&target| {
+            let upper_pos = source_dates.partition_point(|&d| d <= target);
+            let lower_pos = upper_pos.saturating_sub(1);
+
+            let (x0, y0) = (source_dates[lower_pos], values[lower_pos]);
+
+            match method {
+                UpsampleMethod::ForwardFill => y0,
+                UpsampleMethod::Linear => match source_dates.get(upper_pos) {
+                    Some(&x1) if x1 != x0 => {
+                        let y1 = values[upper_pos];
+                        let t = (target - x0).whole_days() as f64 / (x1 - x0).whole_days() as f64;
+                        y0 + (y1 - y0) * t
+                    }
+                    _ => y0,
+                },
+            }
+        })
+        .collect()
+}
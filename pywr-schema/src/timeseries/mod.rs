@@ -15,6 +15,8 @@ use thiserror::Error;
 
 use crate::{parameters::ParameterMeta, SchemaError};
 
+pub use self::align_and_resample::{AggregationFunction, Frequency, UpsampleMethod};
+use self::align_and_resample::align_and_resample;
 use self::polars_dataset::PolarsDataset;
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
@@ -29,14 +31,50 @@ pub struct Timeseries {
     #[serde(flatten)]
     meta: ParameterMeta,
     provider: TimeseriesProvider,
+    /// The name of the date/datetime column in the loaded data. Defaults to `"date"`.
+    #[serde(default = "Timeseries::default_date_column")]
+    date_column: String,
+    /// The native sampling frequency of the source data, used to decide whether it needs to be
+    /// upsampled or downsampled onto the model's own time index. Defaults to [`Frequency::Daily`],
+    /// i.e. no resampling.
+    #[serde(default = "Timeseries::default_frequency")]
+    frequency: Frequency,
+    /// How the series is reduced if its native `frequency` is finer than the model's timestep.
+    #[serde(default = "Timeseries::default_aggregation")]
+    aggregation: AggregationFunction,
+    /// How the series is expanded if its native `frequency` is coarser than the model's timestep.
+    #[serde(default)]
+    upsample_method: UpsampleMethod,
 }
 
 impl Timeseries {
+    fn default_date_column() -> String {
+        "date".to_string()
+    }
+
+    fn default_frequency() -> Frequency {
+        Frequency::Daily
+    }
+
+    fn default_aggregation() -> AggregationFunction {
+        AggregationFunction::Mean
+    }
+
     pub fn load(&self, domain: &ModelDomain, data_path: Option<&Path>) -> Result<DataFrame, SchemaError> {
-        match &self.provider {
-            TimeseriesProvider::Polars(dataset) => dataset.load(self.meta.name.as_str(), data_path, domain),
+        let df = match &self.provider {
+            TimeseriesProvider::Polars(dataset) => dataset.load(self.meta.name.as_str(), data_path, domain)?,
             TimeseriesProvider::Pandas => todo!(),
-        }
+        };
+
+        align_and_resample(
+            self.meta.name.as_str(),
+            df,
+            self.date_column.as_str(),
+            self.frequency,
+            self.aggregation,
+            self.upsample_method,
+            domain,
+        )
     }
 }
 
@@ -0,0 +1,128 @@
+use crate::SchemaError;
+use object_store::aws::AmazonS3Builder;
+use object_store::http::HttpBuilder;
+use object_store::local::LocalFileSystem;
+use object_store::{ObjectStore, path::Path as ObjectPath};
+use polars::prelude::*;
+use pywr_core::models::ModelDomain;
+use std::path::Path;
+use std::sync::Arc;
+use url::Url;
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy)]
+pub enum PolarsDatasetFormat {
+    Csv,
+    Parquet,
+}
+
+/// A Polars-backed timeseries dataset.
+///
+/// `url` may be a plain local path (resolved relative to the model's `data_path`), or a URI with
+/// one of the following schemes, in which case the data is streamed via the `object_store` crate
+/// rather than requiring a pre-synced local copy:
+///  - `file://` an absolute local path
+///  - `s3://bucket/key` an S3-compatible object store; `region` and credentials are taken from
+///    `aws_region`/`aws_access_key_id`/`aws_secret_access_key` or the usual environment fallbacks.
+///  - `http(s)://` a plain HTTP(S) endpoint.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct PolarsDataset {
+    url: String,
+    format: PolarsDatasetFormat,
+    #[serde(default)]
+    aws_region: Option<String>,
+    #[serde(default)]
+    aws_access_key_id: Option<String>,
+    #[serde(default)]
+    aws_secret_access_key: Option<String>,
+}
+
+impl PolarsDataset {
+    pub fn load(&self, name: &str, data_path: Option<&Path>, domain: &ModelDomain) -> Result<DataFrame, SchemaError> {
+        let _ = domain;
+
+        let bytes = match Url::parse(&self.url) {
+            Ok(url) if url.scheme() != "" && url.scheme().len() > 1 => self.fetch_remote(name, &url)?,
+            _ => {
+                // Not a URI (e.g. no scheme, or a Windows drive letter parsed as one); treat
+                // `url` as a plain local path, resolved relative to `data_path` like before.
+                let path = match data_path {
+                    Some(data_path) => data_path.join(&self.url),
+                    None => Path::new(&self.url).to_owned(),
+                };
+                std::fs::read(&path)
+                    .map_err(|e| SchemaError::IO(format!("Failed to read timeseries `{name}` from {path:?}: {e}")))?
+            }
+        };
+
+        self.bytes_to_dataframe(name, bytes)
+    }
+
+    fn fetch_remote(&self, name: &str, url: &Url) -> Result<Vec<u8>, SchemaError> {
+        let rt = tokio::runtime::Runtime::new()
+            .map_err(|e| SchemaError::IO(format!("Failed to start async runtime for `{name}`: {e}")))?;
+
+        rt.block_on(async {
+            let (store, path): (Arc<dyn ObjectStore>, ObjectPath) = match url.scheme() {
+                "s3" => {
+                    let bucket = url.host_str().ok_or_else(|| {
+                        SchemaError::IO(format!("Timeseries `{name}` S3 URL is missing a bucket name."))
+                    })?;
+                    let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+                    if let Some(region) = &self.aws_region {
+                        builder = builder.with_region(region);
+                    }
+                    if let Some(key) = &self.aws_access_key_id {
+                        builder = builder.with_access_key_id(key);
+                    }
+                    if let Some(secret) = &self.aws_secret_access_key {
+                        builder = builder.with_secret_access_key(secret);
+                    }
+                    let store = builder
+                        .build()
+                        .map_err(|e| SchemaError::IO(format!("Failed to build S3 store for `{name}`: {e}")))?;
+                    (Arc::new(store), ObjectPath::from(url.path()))
+                }
+                "http" | "https" => {
+                    let store = HttpBuilder::new()
+                        .with_url(format!("{}://{}", url.scheme(), url.authority()))
+                        .build()
+                        .map_err(|e| SchemaError::IO(format!("Failed to build HTTP store for `{name}`: {e}")))?;
+                    (Arc::new(store), ObjectPath::from(url.path()))
+                }
+                "file" => (
+                    Arc::new(LocalFileSystem::new()),
+                    ObjectPath::from(url.path()),
+                ),
+                scheme => {
+                    return Err(SchemaError::IO(format!(
+                        "Unsupported object store scheme '{scheme}' for timeseries `{name}`."
+                    )))
+                }
+            };
+
+            let result = store
+                .get(&path)
+                .await
+                .map_err(|e| SchemaError::IO(format!("Failed to fetch timeseries `{name}` from {url}: {e}")))?;
+            let bytes = result
+                .bytes()
+                .await
+                .map_err(|e| SchemaError::IO(format!("Failed to read timeseries `{name}` body from {url}: {e}")))?;
+
+            Ok(bytes.to_vec())
+        })
+    }
+
+    fn bytes_to_dataframe(&self, name: &str, bytes: Vec<u8>) -> Result<DataFrame, SchemaError> {
+        let cursor = std::io::Cursor::new(bytes);
+        let df = match self.format {
+            PolarsDatasetFormat::Csv => CsvReader::new(cursor)
+                .finish()
+                .map_err(|e| SchemaError::IO(format!("Failed to parse CSV timeseries `{name}`: {e}")))?,
+            PolarsDatasetFormat::Parquet => ParquetReader::new(cursor)
+                .finish()
+                .map_err(|e| SchemaError::IO(format!("Failed to parse Parquet timeseries `{name}`: {e}")))?,
+        };
+        Ok(df)
+    }
+}
@@ -0,0 +1,70 @@
+use super::{NetworkState, Parameter, ParameterIndex, ParameterMeta, ParameterType, PywrError};
+use crate::scenario::ScenarioIndex;
+use crate::state::ParameterState;
+use crate::timestep::Timestep;
+
+/// The reduction function [`AggregatedParameter::compute`] applies across its constituent
+/// parameters' values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AggFunc {
+    Sum,
+    Product,
+    Mean,
+    Min,
+    Max,
+}
+
+/// Combines the values of several other parameters into one, via `agg_func`.
+pub struct AggregatedParameter {
+    meta: ParameterMeta,
+    parameters: Vec<ParameterIndex>,
+    agg_func: AggFunc,
+}
+
+impl AggregatedParameter {
+    pub fn new(name: &str, parameters: Vec<ParameterIndex>, agg_func: AggFunc) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            parameters,
+            agg_func,
+        }
+    }
+}
+
+impl Parameter for AggregatedParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+
+    /// Every constituent parameter is read during `compute`, so the scheduler must have already
+    /// evaluated all of them first.
+    fn dependencies(&self) -> Vec<ParameterType> {
+        self.parameters.iter().map(|&idx| ParameterType::Parameter(idx)).collect()
+    }
+
+    fn compute(
+        &mut self,
+        _timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        _network_state: &NetworkState,
+        parameter_state: &ParameterState,
+    ) -> Result<f64, PywrError> {
+        let values = self
+            .parameters
+            .iter()
+            .map(|&idx| parameter_state.get_parameter_value(idx))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if values.is_empty() {
+            return Err(PywrError::NoMetricsToAggregate);
+        }
+
+        match self.agg_func {
+            AggFunc::Sum => Ok(values.iter().sum()),
+            AggFunc::Product => Ok(values.iter().product()),
+            AggFunc::Mean => Ok(values.iter().sum::<f64>() / values.len() as f64),
+            AggFunc::Min => values.into_iter().reduce(f64::min).ok_or(PywrError::NoMetricsToAggregate),
+            AggFunc::Max => values.into_iter().reduce(f64::max).ok_or(PywrError::NoMetricsToAggregate),
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use crate::metric::Metric;
+use crate::parameters::interpolate::{interpolate, Interpolation};
+use crate::parameters::{IndexParameter, Parameter, ParameterMeta};
+use crate::scenario::ScenarioIndex;
+use crate::state::State;
+use crate::timestep::Timestep;
+use crate::PywrError;
+use std::any::Any;
+
+/// Which zone of a series of control curves `value` currently falls in.
+///
+/// `control_curves` must be supplied highest-threshold-first. Returns `0` if `value` is at or
+/// above the first (highest) curve, `control_curves.len()` if it is below the last (lowest)
+/// curve, otherwise the index of the highest curve `value` is at or above.
+fn control_curve_zone(value: f64, control_curves: &[f64]) -> usize {
+    control_curves.iter().position(|&cc| value >= cc).unwrap_or(control_curves.len())
+}
+
+fn evaluate_all(metrics: &[Metric], state: &State) -> Result<Vec<f64>, PywrError> {
+    metrics.iter().map(|m| m.get_value(state)).collect()
+}
+
+/// Returns a constant value depending on which zone of a series of control curves the current
+/// value of `metric` (typically a reservoir's proportional volume) falls in.
+///
+/// `values` must have one more entry than `control_curves`: the zone above the first curve, each
+/// zone between two consecutive curves, and the zone below the last curve.
+pub struct ControlCurveParameter {
+    meta: ParameterMeta,
+    metric: Metric,
+    control_curves: Vec<Metric>,
+    values: Vec<Metric>,
+}
+
+impl ControlCurveParameter {
+    pub fn new(name: &str, metric: Metric, control_curves: Vec<Metric>, values: Vec<Metric>) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            metric,
+            control_curves,
+            values,
+        }
+    }
+}
+
+impl Parameter for ControlCurveParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+
+    fn compute(
+        &self,
+        _timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn Any>>,
+    ) -> Result<f64, PywrError> {
+        let value = self.metric.get_value(state)?;
+        let control_curves = evaluate_all(&self.control_curves, state)?;
+        let zone = control_curve_zone(value, &control_curves);
+        self.values[zone].get_value(state)
+    }
+}
+
+/// Returns the index of the zone of a series of control curves the current value of `metric`
+/// falls in. See [`ControlCurveParameter`] for the zone-numbering convention.
+pub struct ControlCurveIndexParameter {
+    meta: ParameterMeta,
+    metric: Metric,
+    control_curves: Vec<Metric>,
+}
+
+impl ControlCurveIndexParameter {
+    pub fn new(name: &str, metric: Metric, control_curves: Vec<Metric>) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            metric,
+            control_curves,
+        }
+    }
+}
+
+impl IndexParameter for ControlCurveIndexParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+
+    fn compute(
+        &self,
+        _timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn Any>>,
+    ) -> Result<usize, PywrError> {
+        let value = self.metric.get_value(state)?;
+        let control_curves = evaluate_all(&self.control_curves, state)?;
+        Ok(control_curve_zone(value, &control_curves))
+    }
+}
+
+/// Interpolates a value between a series of control curves, using [`interpolate`] with one
+/// breakpoint per curve threshold (plus the `0.0`/`1.0` extremes), rather than stepping between
+/// zones like [`ControlCurveParameter`] does.
+///
+/// `control_curves` must be supplied highest-threshold-first and assumed to lie within `[0, 1]`
+/// (e.g. a reservoir's proportional volume); `values` must have one more entry than
+/// `control_curves`, in the same zone ordering as [`ControlCurveParameter`].
+pub struct InterpolatedParameter {
+    meta: ParameterMeta,
+    metric: Metric,
+    control_curves: Vec<Metric>,
+    values: Vec<Metric>,
+    interpolation: Interpolation,
+}
+
+impl InterpolatedParameter {
+    pub fn new(
+        name: &str,
+        metric: Metric,
+        control_curves: Vec<Metric>,
+        values: Vec<Metric>,
+        interpolation: Interpolation,
+    ) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            metric,
+            control_curves,
+            values,
+            interpolation,
+        }
+    }
+
+    /// Build the ascending `(x, y)` breakpoints [`interpolate`] needs from the descending control
+    /// curve thresholds and their bracketed zone values: `0.0` paired with the bottom zone's
+    /// value, each curve threshold paired with the value of the zone immediately below it, and
+    /// `1.0` paired with the top zone's value.
+    fn breakpoints(control_curves: &[f64], values: &[f64]) -> Vec<(f64, f64)> {
+        let n = control_curves.len();
+        let mut points = Vec::with_capacity(n + 2);
+        points.push((0.0, values[n]));
+        for i in (0..n).rev() {
+            points.push((control_curves[i], values[i + 1]));
+        }
+        points.push((1.0, values[0]));
+        points
+    }
+}
+
+impl Parameter for InterpolatedParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+
+    fn compute(
+        &self,
+        _timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn Any>>,
+    ) -> Result<f64, PywrError> {
+        let value = self.metric.get_value(state)?;
+        let control_curves = evaluate_all(&self.control_curves, state)?;
+        let values = evaluate_all(&self.values, state)?;
+        let points = Self::breakpoints(&control_curves, &values);
+        Ok(interpolate(self.interpolation, value, &points))
+    }
+}
+
+/// Interpolates between a `[min, max]` pair of values within whichever zone of a series of
+/// control curves the current value of `metric` falls in, scaling `value`'s position within that
+/// zone's own bounds (the curves above/below it, or `maximum`/`minimum` for the top/bottom zone)
+/// to `[0, 1]` before applying `interpolation`.
+pub struct PiecewiseInterpolatedParameter {
+    meta: ParameterMeta,
+    metric: Metric,
+    control_curves: Vec<Metric>,
+    values: Vec<[f64; 2]>,
+    maximum: f64,
+    minimum: f64,
+    interpolation: Interpolation,
+}
+
+impl PiecewiseInterpolatedParameter {
+    pub fn new(
+        name: &str,
+        metric: Metric,
+        control_curves: Vec<Metric>,
+        values: Vec<[f64; 2]>,
+        maximum: f64,
+        minimum: f64,
+        interpolation: Interpolation,
+    ) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            metric,
+            control_curves,
+            values,
+            maximum,
+            minimum,
+            interpolation,
+        }
+    }
+}
+
+impl Parameter for PiecewiseInterpolatedParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+
+    fn compute(
+        &self,
+        _timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        state: &State,
+        _internal_state: &mut Option<Box<dyn Any>>,
+    ) -> Result<f64, PywrError> {
+        let value = self.metric.get_value(state)?;
+        let control_curves = evaluate_all(&self.control_curves, state)?;
+        let n = control_curves.len();
+        let zone = control_curve_zone(value, &control_curves);
+
+        let upper_bound = if zone == 0 { self.maximum } else { control_curves[zone - 1] };
+        let lower_bound = if zone == n { self.minimum } else { control_curves[zone] };
+        let [value_at_lower, value_at_upper] = self.values[zone];
+
+        let points = [(lower_bound, value_at_lower), (upper_bound, value_at_upper)];
+        Ok(interpolate(self.interpolation, value, &points))
+    }
+}
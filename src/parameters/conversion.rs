@@ -0,0 +1,117 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A unit or type conversion applied to a raw schema value exactly once, at parameter
+/// construction time, so that `compute` itself never has to branch on units.
+///
+/// Model files commonly mix units (megalitres/day, cubic metres/second, acre-feet); rather than
+/// requiring every value to be pre-converted by hand, a parameter can be given a `Conversion` and
+/// will apply it eagerly via [`Conversion::convert`].
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Conversion {
+    /// No conversion; the value is used as-is. This is the default.
+    AsIs,
+    /// Multiply by a constant factor.
+    Scale(f64),
+    /// Multiply by `scale` and then add `offset`.
+    Linear { scale: f64, offset: f64 },
+    /// Megalitres/day to cubic metres/second.
+    MlPerDayToCumecs,
+    /// Cubic metres/second to megalitres/day.
+    CumecsToMlPerDay,
+    /// Acre-feet to cubic metres.
+    AcreFeetToCubicMetres,
+    /// Cubic metres to acre-feet.
+    CubicMetresToAcreFeet,
+}
+
+impl Default for Conversion {
+    fn default() -> Self {
+        Self::AsIs
+    }
+}
+
+impl Conversion {
+    pub fn convert(&self, value: f64) -> f64 {
+        match self {
+            Self::AsIs => value,
+            Self::Scale(factor) => value * factor,
+            Self::Linear { scale, offset } => value * scale + offset,
+            Self::MlPerDayToCumecs => value * 1_000.0 / 86_400.0,
+            Self::CumecsToMlPerDay => value * 86_400.0 / 1_000.0,
+            Self::AcreFeetToCubicMetres => value * 1_233.481_837_547_52,
+            Self::CubicMetresToAcreFeet => value / 1_233.481_837_547_52,
+        }
+    }
+}
+
+/// The schema's `conversion` field named something [`Conversion::from_str`] does not recognise.
+#[derive(Debug, PartialEq)]
+pub struct UnknownConversionError(pub String);
+
+impl fmt::Display for UnknownConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown unit/type conversion `{}`", self.0)
+    }
+}
+
+impl Error for UnknownConversionError {}
+
+impl FromStr for Conversion {
+    type Err = UnknownConversionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "as_is" | "none" => Ok(Self::AsIs),
+            "Ml/d->m3/s" => Ok(Self::MlPerDayToCumecs),
+            "m3/s->Ml/d" => Ok(Self::CumecsToMlPerDay),
+            "acre-ft->m3" => Ok(Self::AcreFeetToCubicMetres),
+            "m3->acre-ft" => Ok(Self::CubicMetresToAcreFeet),
+            _ => {
+                if let Some(factor) = s.strip_prefix("scale:") {
+                    return factor
+                        .parse::<f64>()
+                        .map(Self::Scale)
+                        .map_err(|_| UnknownConversionError(s.to_string()));
+                }
+                if let Some(rest) = s.strip_prefix("linear:") {
+                    let (scale, offset) = rest.split_once(',').ok_or_else(|| UnknownConversionError(s.to_string()))?;
+                    let scale: f64 = scale.parse().map_err(|_| UnknownConversionError(s.to_string()))?;
+                    let offset: f64 = offset.parse().map_err(|_| UnknownConversionError(s.to_string()))?;
+                    return Ok(Self::Linear { scale, offset });
+                }
+                Err(UnknownConversionError(s.to_string()))
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for Conversion {
+    type Error = UnknownConversionError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Conversion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AsIs => write!(f, "as_is"),
+            Self::Scale(factor) => write!(f, "scale:{factor}"),
+            Self::Linear { scale, offset } => write!(f, "linear:{scale},{offset}"),
+            Self::MlPerDayToCumecs => write!(f, "Ml/d->m3/s"),
+            Self::CumecsToMlPerDay => write!(f, "m3/s->Ml/d"),
+            Self::AcreFeetToCubicMetres => write!(f, "acre-ft->m3"),
+            Self::CubicMetresToAcreFeet => write!(f, "m3->acre-ft"),
+        }
+    }
+}
+
+impl From<Conversion> for String {
+    fn from(conversion: Conversion) -> Self {
+        conversion.to_string()
+    }
+}
@@ -0,0 +1,96 @@
+/// The interpolation scheme used to evaluate a value between a series of `(x, y)` breakpoints.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub enum Interpolation {
+    /// Piecewise-linear interpolation between adjacent breakpoints.
+    #[default]
+    Linear,
+    /// The value of the nearest breakpoint.
+    Nearest,
+    /// The value of the breakpoint immediately to the left (i.e. the previous threshold).
+    StepLeft,
+    /// The value of the breakpoint immediately to the right (i.e. the next threshold).
+    StepRight,
+    /// Monotone cubic (PCHIP) interpolation; guarantees no overshoot beyond the breakpoint values.
+    Pchip,
+}
+
+/// Evaluate a value at `x` by interpolating between `points`, a series of `(x, y)` breakpoints
+/// sorted by ascending `x`, using the given `interpolation` scheme.
+///
+/// `x` is clamped to the range of the breakpoints.
+pub fn interpolate(interpolation: Interpolation, x: f64, points: &[(f64, f64)]) -> f64 {
+    if points.is_empty() {
+        return 0.0;
+    }
+    if points.len() == 1 || x <= points[0].0 {
+        return points[0].1;
+    }
+    if x >= points[points.len() - 1].0 {
+        return points[points.len() - 1].1;
+    }
+
+    // Find the segment `i` such that points[i].0 <= x < points[i + 1].0
+    let i = points.windows(2).position(|w| x < w[1].0).unwrap_or(points.len() - 2);
+    let (x0, y0) = points[i];
+    let (x1, y1) = points[i + 1];
+
+    match interpolation {
+        Interpolation::Linear => y0 + (y1 - y0) * (x - x0) / (x1 - x0),
+        Interpolation::Nearest => {
+            if (x - x0).abs() <= (x1 - x).abs() {
+                y0
+            } else {
+                y1
+            }
+        }
+        Interpolation::StepLeft => y0,
+        Interpolation::StepRight => y1,
+        Interpolation::Pchip => pchip_interpolate(points, i, x),
+    }
+}
+
+/// Evaluate the PCHIP cubic Hermite interpolant on the segment `[points[i], points[i + 1]]`.
+///
+/// Derivatives at each breakpoint are derived from the harmonic mean of the adjacent secant
+/// slopes, with the derivative zeroed wherever the adjacent secants change sign. This guarantees
+/// the interpolant is monotonic on each segment and never overshoots the breakpoint values.
+fn pchip_interpolate(points: &[(f64, f64)], i: usize, x: f64) -> f64 {
+    let secant = |a: usize, b: usize| (points[b].1 - points[a].1) / (points[b].0 - points[a].0);
+
+    let derivative_at = |j: usize| -> f64 {
+        if j == 0 {
+            secant(0, 1)
+        } else if j == points.len() - 1 {
+            secant(points.len() - 2, points.len() - 1)
+        } else {
+            let s0 = secant(j - 1, j);
+            let s1 = secant(j, j + 1);
+            if s0 == 0.0 || s1 == 0.0 || s0.signum() != s1.signum() {
+                0.0
+            } else {
+                // Weighted harmonic mean of the two secants.
+                let w0 = 2.0 * (points[j + 1].0 - points[j].0) + (points[j].0 - points[j - 1].0);
+                let w1 = (points[j + 1].0 - points[j].0) + 2.0 * (points[j].0 - points[j - 1].0);
+                (w0 + w1) / (w0 / s0 + w1 / s1)
+            }
+        }
+    };
+
+    let (x0, y0) = points[i];
+    let (x1, y1) = points[i + 1];
+    let m0 = derivative_at(i);
+    let m1 = derivative_at(i + 1);
+
+    let h = x1 - x0;
+    let t = (x - x0) / h;
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    // Cubic Hermite basis functions.
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + t;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+
+    h00 * y0 + h10 * h * m0 + h01 * y1 + h11 * h * m1
+}
@@ -2,22 +2,29 @@ mod aggregated;
 mod aggregated_index;
 pub mod asymmetric;
 pub mod control_curves;
+mod conversion;
 pub mod indexed_array;
+mod interpolate;
 mod max;
 mod negative;
 mod polynomial;
 mod profiles;
 pub mod py;
+mod rolling_aggregation;
 pub mod simple_wasm;
 mod threshold;
 
 // Re-imports
 pub use aggregated::{AggFunc, AggregatedParameter};
 pub use aggregated_index::{AggIndexFunc, AggregatedIndexParameter};
+pub use control_curves::{ControlCurveIndexParameter, ControlCurveParameter, InterpolatedParameter, PiecewiseInterpolatedParameter};
+pub use conversion::{Conversion, UnknownConversionError};
+pub use interpolate::{interpolate, Interpolation};
 pub use max::MaxParameter;
 pub use negative::NegativeParameter;
 pub use polynomial::Polynomial1DParameter;
 pub use profiles::{DailyProfileParameter, MonthlyProfileParameter, UniformDrawdownProfileParameter};
+pub use rolling_aggregation::{RollingAggFunc, RollingAggregationParameter};
 pub use threshold::{Predicate, ThresholdParameter};
 
 use super::{NetworkState, PywrError};
@@ -26,15 +33,15 @@ use crate::scenario::ScenarioIndex;
 
 use crate::state::ParameterState;
 use crate::timestep::Timestep;
-use ndarray::{Array1, Array2};
+use ndarray::{Array1, Array2, ArrayD, IxDyn};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::ops::Deref;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct ParameterIndex(usize);
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct IndexParameterIndex(usize);
 
 impl ParameterIndex {
@@ -102,6 +109,13 @@ pub trait Parameter {
         Ok(())
     }
     fn before(&self) {}
+    /// The other parameters this parameter reads during [`Parameter::compute`], if any. The
+    /// scheduler uses this to fix a valid compute order and to evaluate independent parameters in
+    /// parallel; a parameter that does not read any other parameter's value can leave this as the
+    /// default empty list.
+    fn dependencies(&self) -> Vec<ParameterType> {
+        Vec::new()
+    }
     fn compute(
         &mut self,
         timestep: &Timestep,
@@ -120,6 +134,11 @@ pub trait IndexParameter {
         Ok(())
     }
     fn before(&self) {}
+    /// The other parameters this parameter reads during [`IndexParameter::compute`], if any. See
+    /// [`Parameter::dependencies`].
+    fn dependencies(&self) -> Vec<ParameterType> {
+        Vec::new()
+    }
     fn compute(
         &mut self,
         timestep: &Timestep,
@@ -129,6 +148,7 @@ pub trait IndexParameter {
     ) -> Result<usize, PywrError>;
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum ParameterType {
     Parameter(ParameterIndex),
     Index(IndexParameterIndex),
@@ -168,6 +188,12 @@ impl ConstantParameter {
             value,
         }
     }
+
+    /// Create a new constant parameter, applying `conversion` to `value` once up-front so
+    /// [`Parameter::compute`] never has to convert units itself.
+    pub fn new_with_conversion(name: &str, value: f64, conversion: Conversion) -> Self {
+        Self::new(name, conversion.convert(value))
+    }
 }
 
 impl Parameter for ConstantParameter {
@@ -197,6 +223,12 @@ impl VectorParameter {
             values,
         }
     }
+
+    /// Create a new vector parameter, applying `conversion` to every value once up-front so
+    /// [`Parameter::compute`] never has to convert units itself.
+    pub fn new_with_conversion(name: &str, values: Vec<f64>, conversion: Conversion) -> Self {
+        Self::new(name, values.into_iter().map(|v| conversion.convert(v)).collect())
+    }
 }
 
 impl Parameter for VectorParameter {
@@ -229,6 +261,12 @@ impl Array1Parameter {
             array,
         }
     }
+
+    /// Create a new array parameter, applying `conversion` to every value once up-front so
+    /// [`Parameter::compute`] never has to convert units itself.
+    pub fn new_with_conversion(name: &str, array: Array1<f64>, conversion: Conversion) -> Self {
+        Self::new(name, array.mapv(|v| conversion.convert(v)))
+    }
 }
 
 impl Parameter for Array1Parameter {
@@ -251,15 +289,36 @@ impl Parameter for Array1Parameter {
 pub struct Array2Parameter {
     meta: ParameterMeta,
     array: Array2<f64>,
+    scenario_group_index: usize,
+    default: Option<f64>,
 }
 
 impl Array2Parameter {
-    pub fn new(name: &str, array: Array2<f64>) -> Self {
+    pub fn new(name: &str, array: Array2<f64>, scenario_group_index: usize, default: Option<f64>) -> Self {
         Self {
             meta: ParameterMeta::new(name),
             array,
+            scenario_group_index,
+            default,
         }
     }
+
+    /// Create a new array parameter, applying `conversion` to every value (and, if present, to
+    /// `default`) once up-front so [`Parameter::compute`] never has to convert units itself.
+    pub fn new_with_conversion(
+        name: &str,
+        array: Array2<f64>,
+        scenario_group_index: usize,
+        default: Option<f64>,
+        conversion: Conversion,
+    ) -> Self {
+        Self::new(
+            name,
+            array.mapv(|v| conversion.convert(v)),
+            scenario_group_index,
+            default.map(|v| conversion.convert(v)),
+        )
+    }
 }
 
 impl Parameter for Array2Parameter {
@@ -269,13 +328,105 @@ impl Parameter for Array2Parameter {
     fn compute(
         &mut self,
         timestep: &Timestep,
-        _scenario_index: &ScenarioIndex,
+        scenario_index: &ScenarioIndex,
         _state: &NetworkState,
         _parameter_state: &ParameterState,
     ) -> Result<f64, PywrError> {
-        // This panics if out-of-bounds
-        // TODO scenarios!
-        Ok(self.array[[timestep.index, 0]])
+        let scenario = *scenario_index
+            .indices
+            .get(self.scenario_group_index)
+            .ok_or(PywrError::ScenarioGroupIndexNotFound)?;
+
+        match self.array.get((timestep.index, scenario)) {
+            Some(v) => Ok(*v),
+            None => self.default.ok_or(PywrError::DataOutOfRange),
+        }
+    }
+}
+
+/// How one axis of an [`ArrayNDParameter`]'s underlying array is indexed on each [`Parameter::compute`] call.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArrayAxis {
+    /// Indexed by the current timestep.
+    Time,
+    /// Indexed by the current scenario's index within the given scenario group.
+    ScenarioGroup(usize),
+    /// Always indexed at this fixed position, regardless of timestep or scenario. Useful for an
+    /// axis the array happens to carry (e.g. a tables-derived sensitivity axis) that this
+    /// particular parameter instance should not vary across.
+    Constant(usize),
+}
+
+/// An N-dimensional generalisation of [`Array2Parameter`]: one value per timestep, varying across
+/// an arbitrary number of axes rather than just one scenario group.
+///
+/// `axes` has one entry per axis of `array`, in order, describing how that axis is indexed; it
+/// normally starts with [`ArrayAxis::Time`] followed by one [`ArrayAxis::ScenarioGroup`] per
+/// varying scenario dimension, but [`ArrayAxis::Constant`] lets any axis be pinned instead.
+pub struct ArrayNDParameter {
+    meta: ParameterMeta,
+    array: ArrayD<f64>,
+    axes: Vec<ArrayAxis>,
+    default: Option<f64>,
+}
+
+impl ArrayNDParameter {
+    pub fn new(name: &str, array: ArrayD<f64>, axes: Vec<ArrayAxis>, default: Option<f64>) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            array,
+            axes,
+            default,
+        }
+    }
+
+    /// Create a new array parameter, applying `conversion` to every value (and, if present, to
+    /// `default`) once up-front so [`Parameter::compute`] never has to convert units itself.
+    pub fn new_with_conversion(
+        name: &str,
+        array: ArrayD<f64>,
+        axes: Vec<ArrayAxis>,
+        default: Option<f64>,
+        conversion: Conversion,
+    ) -> Self {
+        Self::new(
+            name,
+            array.mapv(|v| conversion.convert(v)),
+            axes,
+            default.map(|v| conversion.convert(v)),
+        )
+    }
+}
+
+impl Parameter for ArrayNDParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+    fn compute(
+        &mut self,
+        timestep: &Timestep,
+        scenario_index: &ScenarioIndex,
+        _state: &NetworkState,
+        _parameter_state: &ParameterState,
+    ) -> Result<f64, PywrError> {
+        let mut index = Vec::with_capacity(self.axes.len());
+
+        for axis in &self.axes {
+            let i = match axis {
+                ArrayAxis::Time => timestep.index,
+                ArrayAxis::ScenarioGroup(group) => *scenario_index
+                    .indices
+                    .get(*group)
+                    .ok_or(PywrError::ScenarioGroupIndexNotFound)?,
+                ArrayAxis::Constant(i) => *i,
+            };
+            index.push(i);
+        }
+
+        match self.array.get(IxDyn(&index)) {
+            Some(v) => Ok(*v),
+            None => self.default.ok_or(PywrError::DataOutOfRange),
+        }
     }
 }
 
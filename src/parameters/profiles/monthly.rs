@@ -4,9 +4,39 @@ use crate::state::ParameterState;
 use crate::timestep::Timestep;
 use crate::{NetworkState, PywrError};
 
+/// The day of the month that each monthly value is anchored to when interpolating between months.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterpDay {
+    First,
+    Fifteenth,
+}
+
+impl InterpDay {
+    fn day(&self) -> u8 {
+        match self {
+            Self::First => 1,
+            Self::Fifteenth => 15,
+        }
+    }
+}
+
+/// How [`MonthlyProfileParameter::compute`] derives a value for a date that falls between two
+/// calendar months.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MonthlyInterpolation {
+    /// Each month's value is held constant for the whole month, producing a step function with a
+    /// discontinuity at the start of each month. This is the default.
+    #[default]
+    None,
+    /// Linearly interpolate between adjacent months' values, treating each month's value as
+    /// anchored to the given day of that month.
+    MonthInterpDay(InterpDay),
+}
+
 pub struct MonthlyProfileParameter {
     meta: ParameterMeta,
     values: [f64; 12],
+    interpolation: MonthlyInterpolation,
 }
 
 impl MonthlyProfileParameter {
@@ -14,6 +44,15 @@ impl MonthlyProfileParameter {
         Self {
             meta: ParameterMeta::new(name),
             values,
+            interpolation: MonthlyInterpolation::None,
+        }
+    }
+
+    pub fn new_with_interpolation(name: &str, values: [f64; 12], interpolation: MonthlyInterpolation) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            values,
+            interpolation,
         }
     }
 }
@@ -29,6 +68,138 @@ impl Parameter for MonthlyProfileParameter {
         _state: &NetworkState,
         _parameter_state: &ParameterState,
     ) -> Result<f64, PywrError> {
-        Ok(self.values[timestep.date.month() as usize])
+        let month = timestep.date.month() as usize;
+        let this_month_value = self.values[month - 1];
+
+        let anchor_day = match self.interpolation {
+            MonthlyInterpolation::None => return Ok(this_month_value),
+            MonthlyInterpolation::MonthInterpDay(anchor) => anchor.day(),
+        };
+
+        let day = timestep.date.day();
+        let year = timestep.date.year();
+
+        // 0-based indices of the neighbouring months, wrapping December <-> January.
+        let prev_month = month.checked_sub(2).unwrap_or(11);
+        let next_month = month % 12;
+
+        if day >= anchor_day {
+            let days_in_month = timestep.date.month().length(year);
+            let t = (day - anchor_day) as f64 / days_in_month as f64;
+            Ok(this_month_value + (self.values[next_month] - this_month_value) * t)
+        } else {
+            let prev = timestep.date.month().previous();
+            let prev_year = if month == 1 { year - 1 } else { year };
+            let days_in_prev_month = prev.length(prev_year);
+            let t = (days_in_prev_month - anchor_day + day) as f64 / days_in_prev_month as f64;
+            let prev_month_value = self.values[prev_month];
+            Ok(prev_month_value + (this_month_value - prev_month_value) * t)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::ScenarioIndex;
+    use crate::timestep::Timestepper;
+    use time::macros::date;
+
+    fn compute_on(param: &mut MonthlyProfileParameter, year: i32, month: time::Month, day: u8) -> f64 {
+        let start = time::Date::from_calendar_date(year, month, day).unwrap();
+        let timestepper = Timestepper::new(start, start, 1);
+        let timestep = &timestepper.timesteps()[0];
+        let si = ScenarioIndex { index: 0, indices: vec![0] };
+        let ns = NetworkState::new();
+        let ps = ParameterState::new();
+
+        param.compute(timestep, &si, &ns, &ps).unwrap()
+    }
+
+    /// Month indices 0 (Jan) to 11 (Dec), scaled by 10 so each month's value is easy to spot.
+    fn values() -> [f64; 12] {
+        std::array::from_fn(|i| i as f64 * 10.0)
+    }
+
+    #[test]
+    fn test_december_interpolates_forward_into_january() {
+        // Regression test for the panic this parameter used to hit on/after 1 December: `month %
+        // 12` wraps `next_month` back to January (index 0) instead of overflowing past 11.
+        let mut param = MonthlyProfileParameter::new_with_interpolation(
+            "test-monthly",
+            values(),
+            MonthlyInterpolation::MonthInterpDay(InterpDay::First),
+        );
+
+        let value = compute_on(&mut param, 2021, time::Month::December, 16);
+
+        let days_in_december = 31.0;
+        let t = (16 - 1) as f64 / days_in_december;
+        let expected = 110.0 + (0.0 - 110.0) * t;
+        assert!((value - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_january_before_anchor_day_interpolates_back_into_december() {
+        // A day before the anchor day in January must wrap `prev_month` back to December (index
+        // 11) rather than underflowing, and use *December's* day count for the interpolation
+        // fraction, not January's.
+        let mut param = MonthlyProfileParameter::new_with_interpolation(
+            "test-monthly",
+            values(),
+            MonthlyInterpolation::MonthInterpDay(InterpDay::Fifteenth),
+        );
+
+        let value = compute_on(&mut param, 2021, time::Month::January, 5);
+
+        let days_in_december = 31.0;
+        let t = (days_in_december - 15.0 + 5.0) / days_in_december;
+        let expected = 110.0 + (0.0 - 110.0) * t;
+        assert!((value - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_previous_month_length_is_leap_year_sensitive() {
+        // A day before the anchor day in March looks back at February, whose length depends on
+        // whether *that* year (not the current one) is a leap year.
+        let mut leap = MonthlyProfileParameter::new_with_interpolation(
+            "test-monthly",
+            values(),
+            MonthlyInterpolation::MonthInterpDay(InterpDay::Fifteenth),
+        );
+        let mut non_leap = MonthlyProfileParameter::new_with_interpolation(
+            "test-monthly",
+            values(),
+            MonthlyInterpolation::MonthInterpDay(InterpDay::Fifteenth),
+        );
+
+        // 2024 is a leap year (Feb has 29 days); 2023 is not (Feb has 28 days).
+        let leap_value = compute_on(&mut leap, 2024, time::Month::March, 1);
+        let non_leap_value = compute_on(&mut non_leap, 2023, time::Month::March, 1);
+
+        let t_leap = (29.0 - 15.0 + 1.0) / 29.0;
+        let t_non_leap = (28.0 - 15.0 + 1.0) / 28.0;
+        let expected_leap = 10.0 + (20.0 - 10.0) * t_leap;
+        let expected_non_leap = 10.0 + (20.0 - 10.0) * t_non_leap;
+
+        assert!((leap_value - expected_leap).abs() < 1e-9);
+        assert!((non_leap_value - expected_non_leap).abs() < 1e-9);
+        assert!((leap_value - non_leap_value).abs() > 1e-9);
+    }
+
+    #[test]
+    fn test_day_after_anchor_within_month_does_not_wrap() {
+        let mut param = MonthlyProfileParameter::new_with_interpolation(
+            "test-monthly",
+            values(),
+            MonthlyInterpolation::MonthInterpDay(InterpDay::First),
+        );
+
+        let value = compute_on(&mut param, 2021, time::Month::June, 10);
+
+        let days_in_june = 30.0;
+        let t = (10 - 1) as f64 / days_in_june;
+        let expected = 50.0 + (60.0 - 50.0) * t;
+        assert!((value - expected).abs() < 1e-9);
     }
 }
@@ -0,0 +1,190 @@
+use crate::metric::Metric;
+use crate::parameters::{Parameter, ParameterMeta};
+use crate::scenario::ScenarioIndex;
+use crate::state::State;
+use crate::timestep::Timestep;
+use crate::PywrError;
+use std::any::Any;
+use std::collections::VecDeque;
+
+/// The reduction function applied over the rolling window by [`RollingAggregationParameter`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RollingAggFunc {
+    Mean,
+    Min,
+    Max,
+    Sum,
+}
+
+struct Internal {
+    buffer: VecDeque<f64>,
+}
+
+/// A parameter that maintains a rolling window of an underlying [`Metric`] and returns a
+/// windowed statistic (mean, min, max or sum) over the last `window_size` timesteps.
+///
+/// During the warm-up period before the buffer has `window_size` entries the statistic is
+/// computed over the partial buffer, unless `initial_value` is given, in which case that
+/// value is substituted until the buffer is full.
+pub struct RollingAggregationParameter {
+    meta: ParameterMeta,
+    metric: Metric,
+    window_size: usize,
+    agg_func: RollingAggFunc,
+    initial_value: Option<f64>,
+}
+
+impl RollingAggregationParameter {
+    pub fn new(
+        name: &str,
+        metric: Metric,
+        window_size: usize,
+        agg_func: RollingAggFunc,
+        initial_value: Option<f64>,
+    ) -> Self {
+        Self {
+            meta: ParameterMeta::new(name),
+            metric,
+            window_size,
+            agg_func,
+            initial_value,
+        }
+    }
+
+    fn reduce(&self, buffer: &VecDeque<f64>) -> f64 {
+        match self.agg_func {
+            RollingAggFunc::Sum => buffer.iter().sum(),
+            RollingAggFunc::Mean => buffer.iter().sum::<f64>() / buffer.len() as f64,
+            RollingAggFunc::Min => buffer.iter().copied().fold(f64::INFINITY, f64::min),
+            RollingAggFunc::Max => buffer.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+
+    /// Push this timestep's underlying metric `value` into `buffer` (evicting the oldest entry
+    /// first if the window is already full), then return the value this parameter should report:
+    /// `self.initial_value` while the buffer is still below `window_size` (if one was given), or
+    /// `self.reduce`'s statistic over the buffer otherwise. Factored out of [`Parameter::compute`]
+    /// so the window-fill/eviction/warm-up behaviour can be tested without needing a [`State`].
+    fn step(&self, buffer: &mut VecDeque<f64>, value: f64) -> f64 {
+        if buffer.len() == self.window_size {
+            buffer.pop_front();
+        }
+        buffer.push_back(value);
+
+        if buffer.len() < self.window_size {
+            if let Some(initial_value) = self.initial_value {
+                return initial_value;
+            }
+        }
+
+        self.reduce(buffer)
+    }
+}
+
+impl Parameter for RollingAggregationParameter {
+    fn meta(&self) -> &ParameterMeta {
+        &self.meta
+    }
+
+    fn setup(
+        &self,
+        _timesteps: &[Timestep],
+        _scenario_index: &ScenarioIndex,
+    ) -> Result<Option<Box<dyn Any>>, PywrError> {
+        let internal = Internal {
+            buffer: VecDeque::with_capacity(self.window_size),
+        };
+        Ok(Some(Box::new(internal)))
+    }
+
+    fn compute(
+        &self,
+        _timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        state: &State,
+        internal_state: &mut Option<Box<dyn Any>>,
+    ) -> Result<f64, PywrError> {
+        let internal = match internal_state {
+            Some(internal) => internal
+                .downcast_mut::<Internal>()
+                .ok_or_else(|| PywrError::InternalParameterError("Internal state did not downcast to the correct type.".to_string()))?,
+            None => return Err(PywrError::InternalParameterError("No internal state defined when one was expected.".to_string())),
+        };
+
+        let value = self.metric.get_value(state)?;
+
+        Ok(self.step(&mut internal.buffer, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param(window_size: usize, agg_func: RollingAggFunc, initial_value: Option<f64>) -> RollingAggregationParameter {
+        RollingAggregationParameter::new("test-rolling", Metric::Constant(0.0), window_size, agg_func, initial_value)
+    }
+
+    /// Before the window is full and no `initial_value` is given, the statistic is computed over
+    /// whatever partial history is available.
+    #[test]
+    fn test_warm_up_without_initial_value() {
+        let p = param(3, RollingAggFunc::Mean, None);
+        let mut buffer = VecDeque::new();
+
+        assert_eq!(p.step(&mut buffer, 2.0), 2.0);
+        assert_eq!(p.step(&mut buffer, 4.0), 3.0);
+        // The window is now full, so this is the first "real" windowed mean.
+        assert_eq!(p.step(&mut buffer, 6.0), 4.0);
+    }
+
+    /// Before the window is full, `initial_value` (when given) is reported verbatim instead of a
+    /// partial-window statistic.
+    #[test]
+    fn test_warm_up_with_initial_value() {
+        let p = param(3, RollingAggFunc::Mean, Some(-1.0));
+        let mut buffer = VecDeque::new();
+
+        assert_eq!(p.step(&mut buffer, 2.0), -1.0);
+        assert_eq!(p.step(&mut buffer, 4.0), -1.0);
+        // The window is now full, so the statistic takes over from `initial_value`.
+        assert_eq!(p.step(&mut buffer, 6.0), 4.0);
+    }
+
+    /// Once the window is full, the oldest entry is evicted as each new one arrives.
+    #[test]
+    fn test_window_fill_and_eviction() {
+        let p = param(2, RollingAggFunc::Sum, None);
+        let mut buffer = VecDeque::new();
+
+        assert_eq!(p.step(&mut buffer, 1.0), 1.0);
+        assert_eq!(p.step(&mut buffer, 2.0), 3.0);
+        // `1.0` is evicted; the window now holds `[2.0, 3.0]`.
+        assert_eq!(p.step(&mut buffer, 3.0), 5.0);
+        assert_eq!(buffer.len(), 2);
+    }
+
+    #[test]
+    fn test_reduce_sum() {
+        let p = param(3, RollingAggFunc::Sum, None);
+        assert_eq!(p.reduce(&VecDeque::from([1.0, 2.0, 3.0])), 6.0);
+    }
+
+    #[test]
+    fn test_reduce_mean() {
+        let p = param(3, RollingAggFunc::Mean, None);
+        assert_eq!(p.reduce(&VecDeque::from([1.0, 2.0, 3.0])), 2.0);
+    }
+
+    #[test]
+    fn test_reduce_min() {
+        let p = param(3, RollingAggFunc::Min, None);
+        assert_eq!(p.reduce(&VecDeque::from([3.0, 1.0, 2.0])), 1.0);
+    }
+
+    #[test]
+    fn test_reduce_max() {
+        let p = param(3, RollingAggFunc::Max, None);
+        assert_eq!(p.reduce(&VecDeque::from([3.0, 1.0, 2.0])), 3.0);
+    }
+}
@@ -1,102 +1,232 @@
-use super::{Parameter, ParameterMeta, PywrError, Timestep};
+use super::{Parameter, ParameterMeta, ParameterType, PywrError, Timestep};
 use crate::model::Model;
 use crate::scenario::ScenarioIndex;
 use crate::state::State;
 use crate::ParameterIndex;
 use std::any::Any;
-use wasmer::{imports, Array, Instance, Module, NativeFunc, Store, WasmPtr};
+use std::sync::{Arc, Mutex};
+use wasmer::{imports, Array, Function, Instance, Module, NativeFunc, Store, WasmPtr};
+use wasmer_compiler_cranelift::Cranelift;
+use wasmer_engine_universal::Universal;
+use wasmer_middlewares::{metering::get_remaining_points, Metering, MeteringPoints};
 
 type ValueFunc = NativeFunc<(WasmPtr<f64, Array>, u32), f64>;
 type SetFunc = NativeFunc<(WasmPtr<f64, Array>, u32, u32, f64), ()>;
 
+/// A simple per-instruction gas cost used by [`Metering`] to bound how long a single
+/// [`SimpleWasmParameter`] call is allowed to run; every operator costs one unit regardless of
+/// kind, which is a crude but cheap-to-compute proxy for execution time.
+fn metering_cost(_operator: &wasmer::wasmparser::Operator) -> u64 {
+    1
+}
+
+/// Print a message from guest WASM to stderr. Imported into the module's `"env"` namespace as
+/// `pywr_log`; the guest passes a pointer/length pair into its own linear memory, which is read
+/// back out using the caller's exported `memory`.
+fn pywr_log(env: &wasmer::FunctionEnv<Arc<wasmer::Memory>>, ptr: WasmPtr<u8, Array>, len: u32) {
+    // NB: this is reached-for-side-effect only; a failure to read the message is not fatal to the
+    // running parameter, so it is swallowed rather than surfaced as a `PywrError`.
+    if let Some(message) = ptr.get_utf8_string(env.data(), len) {
+        eprintln!("[wasm] {}", message);
+    }
+}
+
+/// Per-call context exposed to the guest via `get_timestep_index`/`get_scenario_index`/
+/// `get_node_volume`. The import table is fixed once in `setup`, but the timestep/scenario/state
+/// it should reflect changes on every [`Parameter::compute`] call, so `compute` refreshes this
+/// (behind the same `Arc<Mutex<_>>` the imports were built with) immediately before invoking the
+/// module's `value` export.
+#[derive(Default)]
+struct WasmContext {
+    timestep_index: u32,
+    scenario_index: u32,
+    /// Node volume snapshot taken at the top of `compute`, indexed by [`crate::node::NodeIndex`].
+    node_volumes: Vec<f64>,
+}
+
+/// Host function `get_timestep_index`: the 0-based index of the timestep currently being solved.
+fn get_timestep_index(env: &wasmer::FunctionEnv<Arc<Mutex<WasmContext>>>) -> u32 {
+    env.data().lock().unwrap().timestep_index
+}
+
+/// Host function `get_scenario_index`: the 0-based index of the scenario currently being solved.
+fn get_scenario_index(env: &wasmer::FunctionEnv<Arc<Mutex<WasmContext>>>) -> u32 {
+    env.data().lock().unwrap().scenario_index
+}
+
+/// Host function `get_node_volume`: the current volume of the node with the given index, from the
+/// snapshot `compute` took at the start of this call. Returns `0.0` for an out-of-range `node_id`
+/// rather than trapping, since a guest passing a bad id is a module bug, not a simulation error.
+fn get_node_volume(env: &wasmer::FunctionEnv<Arc<Mutex<WasmContext>>>, node_id: u32) -> f64 {
+    env.data()
+        .lock()
+        .unwrap()
+        .node_volumes
+        .get(node_id as usize)
+        .copied()
+        .unwrap_or(0.0)
+}
+
 pub struct SimpleWasmParameter {
     meta: ParameterMeta,
     src: Vec<u8>,
     parameters: Vec<ParameterIndex>,
+    /// The maximum number of WASM operators a single [`Parameter::compute`] call may execute
+    /// before the metering middleware traps it, guarding against a malicious or buggy module
+    /// looping forever inside the simulation's hot path.
+    gas_limit: u64,
 }
 
 impl SimpleWasmParameter {
     pub fn new(name: &str, src: Vec<u8>, parameters: Vec<ParameterIndex>) -> Self {
+        Self::new_with_gas_limit(name, src, parameters, 10_000_000)
+    }
+
+    pub fn new_with_gas_limit(name: &str, src: Vec<u8>, parameters: Vec<ParameterIndex>, gas_limit: u64) -> Self {
         Self {
             meta: ParameterMeta::new(name),
             src,
             parameters,
+            gas_limit,
         }
     }
 }
 
 struct Internal {
+    instance: Instance,
     func: ValueFunc,
     set_func: SetFunc,
     ptr: WasmPtr<f64, Array>,
+    gas_limit: u64,
+    context: Arc<Mutex<WasmContext>>,
 }
 
 impl Parameter for SimpleWasmParameter {
     fn meta(&self) -> &ParameterMeta {
         &self.meta
     }
+    fn dependencies(&self) -> Vec<ParameterType> {
+        self.parameters.iter().map(|&idx| ParameterType::Parameter(idx)).collect()
+    }
     fn setup(
         &self,
         _timesteps: &[Timestep],
         _scenario_index: &ScenarioIndex,
     ) -> Result<Option<Box<dyn Any + Send>>, PywrError> {
-        let store = Store::default();
-        let module = Module::new(&store, &self.src).unwrap();
+        let metering = Arc::new(Metering::new(self.gas_limit, metering_cost));
+        let mut compiler_config = Cranelift::default();
+        compiler_config.push_middleware(metering);
+
+        let store = Store::new(&Universal::new(compiler_config).engine());
+        let module = Module::new(&store, &self.src)
+            .map_err(|e| PywrError::InternalParameterError(format!("Failed to compile WASM module `{}`: {e}", self.meta.name)))?;
+
+        let context = Arc::new(Mutex::new(WasmContext::default()));
+
+        let import_object = imports! {
+            "env" => {
+                "pywr_log" => Function::new_native(&store, pywr_log),
+                "get_timestep_index" => Function::new_native_with_env(&store, context.clone(), get_timestep_index),
+                "get_scenario_index" => Function::new_native_with_env(&store, context.clone(), get_scenario_index),
+                "get_node_volume" => Function::new_native_with_env(&store, context.clone(), get_node_volume),
+            },
+        };
 
-        // Create an empty import object.
-        let import_object = imports! {};
+        let instance = Instance::new(&module, &import_object)
+            .map_err(|e| PywrError::InternalParameterError(format!("Failed to instantiate WASM module `{}`: {e}", self.meta.name)))?;
 
-        // Let's instantiate the Wasm module.
-        // TODO handle these WASM errors.
-        let instance = Instance::new(&module, &import_object).unwrap();
-        let func = instance.exports.get_function("value").unwrap().native().unwrap();
+        let func = instance
+            .exports
+            .get_function("value")
+            .map_err(|e| PywrError::InternalParameterError(format!("WASM module `{}` has no `value` export: {e}", self.meta.name)))?
+            .native()
+            .map_err(|e| PywrError::InternalParameterError(format!("WASM module `{}` export `value` has the wrong signature: {e}", self.meta.name)))?;
 
-        let set_func = instance.exports.get_function("set").unwrap().native().unwrap();
+        let set_func = instance
+            .exports
+            .get_function("set")
+            .map_err(|e| PywrError::InternalParameterError(format!("WASM module `{}` has no `set` export: {e}", self.meta.name)))?
+            .native()
+            .map_err(|e| PywrError::InternalParameterError(format!("WASM module `{}` export `set` has the wrong signature: {e}", self.meta.name)))?;
 
         let alloc = instance
             .exports
             .get_function("alloc")
-            .unwrap()
+            .map_err(|e| PywrError::InternalParameterError(format!("WASM module `{}` has no `alloc` export: {e}", self.meta.name)))?
             .native::<u32, WasmPtr<f64, Array>>()
-            .unwrap();
-
-        let ptr = alloc.call(self.parameters.len() as u32).unwrap();
-
-        let internal_state = Internal { func, set_func, ptr };
+            .map_err(|e| PywrError::InternalParameterError(format!("WASM module `{}` export `alloc` has the wrong signature: {e}", self.meta.name)))?;
+
+        let ptr = alloc
+            .call(self.parameters.len() as u32)
+            .map_err(|e| PywrError::InternalParameterError(format!("Error calling WASM `alloc` export: {:?}.", e)))?;
+
+        let internal_state = Internal {
+            instance,
+            func,
+            set_func,
+            ptr,
+            gas_limit: self.gas_limit,
+            context,
+        };
 
         Ok(Some(Box::new(internal_state)))
     }
 
     fn compute(
         &self,
-        _timestep: &Timestep,
-        _scenario_index: &ScenarioIndex,
-        _model: &Model,
+        timestep: &Timestep,
+        scenario_index: &ScenarioIndex,
+        model: &Model,
         state: &State,
         internal_state: &mut Option<Box<dyn Any + Send>>,
     ) -> Result<f64, PywrError> {
         // Downcast the internal state to the correct type
-        let funcs = match internal_state {
-            Some(internal) => match internal.downcast_mut::<Internal>() {
-                Some(pa) => pa,
-                None => panic!("Internal state did not downcast to the correct type! :("),
-            },
-            None => panic!("No internal state defined when one was expected! :("),
+        let internal = match internal_state {
+            Some(internal) => internal
+                .downcast_mut::<Internal>()
+                .ok_or_else(|| PywrError::InternalParameterError("Internal state did not downcast to the correct type.".to_string()))?,
+            None => return Err(PywrError::InternalParameterError("No internal state defined when one was expected.".to_string())),
         };
 
+        // Each call gets a fresh gas allowance; otherwise every subsequent timestep's compute
+        // would run against whatever was left over from the last one.
+        wasmer_middlewares::metering::set_remaining_points(&internal.instance, internal.gas_limit);
+
+        // Refresh the shared context so `get_timestep_index`/`get_scenario_index`/
+        // `get_node_volume` see this call's timestep, scenario and node volumes rather than
+        // whatever the previous call left behind.
+        {
+            let mut context = internal.context.lock().unwrap();
+            context.timestep_index = timestep.index as u32;
+            context.scenario_index = scenario_index.index as u32;
+            context.node_volumes = model
+                .nodes()
+                .iter()
+                .map(|node| state.get_network_state().get_node_volume(&node.index()))
+                .collect::<Result<_, _>>()?;
+        }
+
         // Assign the parameter values to the WASM's internal memory
         let len = self.parameters.len() as u32;
         for (idx, p) in self.parameters.iter().enumerate() {
             let v = state.get_parameter_value(*p)?;
 
-            funcs.set_func.call(funcs.ptr, len, idx as u32, v).map_err(|e| {
+            internal.set_func.call(internal.ptr, len, idx as u32, v).map_err(|e| {
                 PywrError::InternalParameterError(format!("Error calling WASM imported function: {:?}.", e))
             })?;
         }
 
         // Calculate the parameter's final value using the WASM function.
-        let value: f64 = funcs.func.call(funcs.ptr, len).map_err(|e| {
-            PywrError::InternalParameterError(format!("Error calling WASM imported function: {:?}.", e))
+        let value: f64 = internal.func.call(internal.ptr, len).map_err(|e| {
+            match get_remaining_points(&internal.instance) {
+                MeteringPoints::Exhausted => PywrError::InternalParameterError(format!(
+                    "WASM module `{}` exceeded its gas limit of {} operators.",
+                    self.meta.name, internal.gas_limit
+                )),
+                MeteringPoints::Remaining(_) => {
+                    PywrError::InternalParameterError(format!("Error calling WASM imported function: {:?}.", e))
+                }
+            }
         })?;
 
         Ok(value)
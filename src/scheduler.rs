@@ -0,0 +1,185 @@
+use crate::parameters::{IndexParameter, IndexParameterIndex, Parameter, ParameterIndex, ParameterType};
+use crate::scenario::ScenarioIndex;
+use crate::solvers::SolverSettings;
+use crate::state::ParameterState;
+use crate::timestep::Timestep;
+use crate::{NetworkState, PywrError};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// One "wavefront" of the dependency graph: parameters whose dependencies have all already been
+/// evaluated (in an earlier level), so they have no remaining dependency on one another and may
+/// be evaluated in any order, including in parallel with one another.
+pub type Level = Vec<ParameterType>;
+
+/// A fixed compute order for a set of parameters, derived from their declared
+/// `Parameter::dependencies`/`IndexParameter::dependencies`.
+///
+/// Parameters reference one another by index with no inherent ordering guarantee; without this,
+/// evaluating them in registration order is fragile, since a parameter could easily end up being
+/// computed before one of the parameters it reads. [`ParameterScheduler::new`] instead performs a
+/// Kahn's-algorithm topological sort into levels, so independent parameters within a level can
+/// later be evaluated in parallel via [`ParameterScheduler::evaluate`].
+pub struct ParameterScheduler {
+    levels: Vec<Level>,
+}
+
+impl ParameterScheduler {
+    /// Build a compute order from every parameter's identity, display name (used only for a
+    /// readable [`PywrError::ParameterCycle`] message) and declared dependencies.
+    pub fn new(parameters: &[(ParameterType, String, Vec<ParameterType>)]) -> Result<Self, PywrError> {
+        let mut names: HashMap<ParameterType, String> = HashMap::new();
+        let mut deps: HashMap<ParameterType, Vec<ParameterType>> = HashMap::new();
+
+        for (node, name, node_deps) in parameters {
+            names.insert(*node, name.clone());
+            deps.insert(*node, node_deps.clone());
+        }
+
+        let mut remaining: HashSet<ParameterType> = deps.keys().copied().collect();
+        let mut levels = Vec::new();
+
+        while !remaining.is_empty() {
+            let level: Vec<ParameterType> = remaining
+                .iter()
+                .copied()
+                .filter(|node| deps[node].iter().all(|dep| !remaining.contains(dep)))
+                .collect();
+
+            if level.is_empty() {
+                let mut cycle: Vec<String> = remaining.iter().map(|node| names[node].clone()).collect();
+                cycle.sort();
+                return Err(PywrError::ParameterCycle(cycle));
+            }
+
+            for node in &level {
+                remaining.remove(node);
+            }
+            levels.push(level);
+        }
+
+        Ok(Self { levels })
+    }
+
+    /// The levels (wavefronts) of the compute order. A level must fully complete before the next
+    /// one starts, but the parameters within a level have no dependency on one another.
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+
+    /// Evaluate every level in order, calling `compute` once per parameter. Parameters within a
+    /// level are run in parallel across a thread pool sized from `settings` (0 threads lets rayon
+    /// size the pool from the available CPUs, matching [`ClpSolverSettings::threads`](crate::solvers::ClpSolverSettings::threads)'s
+    /// own fallback), or serially, in level order, when `settings.parallel()` is `false`.
+    pub fn evaluate<S, F>(&self, settings: &S, compute: F) -> Result<(), PywrError>
+    where
+        S: SolverSettings,
+        F: Fn(ParameterType) -> Result<(), PywrError> + Sync,
+    {
+        if !settings.parallel() {
+            for level in &self.levels {
+                for &node in level {
+                    compute(node)?;
+                }
+            }
+            return Ok(());
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(settings.threads())
+            .build()
+            .map_err(|e| PywrError::InternalParameterError(format!("Failed to build parameter evaluation thread pool: {e}")))?;
+
+        for level in &self.levels {
+            pool.install(|| level.par_iter().try_for_each(|&node| compute(node)))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The registered parameters and index-parameters for a single network, and the entry point the
+/// run loop uses to compute all of them for a timestep.
+///
+/// Each parameter is held behind its own [`Mutex`] rather than requiring `&mut self` on the whole
+/// collection, since [`ParameterScheduler::evaluate`] may call into several of them concurrently
+/// from the worker threads evaluating a single level.
+pub struct ParameterCollection {
+    parameters: Vec<Mutex<Box<dyn Parameter + Send>>>,
+    index_parameters: Vec<Mutex<Box<dyn IndexParameter + Send>>>,
+}
+
+impl ParameterCollection {
+    pub fn new() -> Self {
+        Self {
+            parameters: Vec::new(),
+            index_parameters: Vec::new(),
+        }
+    }
+
+    pub fn add_parameter(&mut self, parameter: Box<dyn Parameter + Send>) -> ParameterIndex {
+        let index = ParameterIndex::new(self.parameters.len());
+        self.parameters.push(Mutex::new(parameter));
+        index
+    }
+
+    pub fn add_index_parameter(&mut self, parameter: Box<dyn IndexParameter + Send>) -> IndexParameterIndex {
+        let index = IndexParameterIndex::new(self.index_parameters.len());
+        self.index_parameters.push(Mutex::new(parameter));
+        index
+    }
+
+    /// Build a [`ParameterScheduler`] from every registered parameter's current `dependencies()`.
+    /// Must be called again (and the old scheduler discarded) whenever a parameter is added, since
+    /// the compute order returned is fixed at build time and does not see later additions.
+    pub fn build_scheduler(&self) -> Result<ParameterScheduler, PywrError> {
+        let mut entries = Vec::with_capacity(self.parameters.len() + self.index_parameters.len());
+
+        for (i, parameter) in self.parameters.iter().enumerate() {
+            let parameter = parameter.lock().unwrap();
+            let index = ParameterType::Parameter(ParameterIndex::new(i));
+            entries.push((index, parameter.name().to_string(), parameter.dependencies()));
+        }
+
+        for (i, parameter) in self.index_parameters.iter().enumerate() {
+            let parameter = parameter.lock().unwrap();
+            let index = ParameterType::Index(IndexParameterIndex::new(i));
+            entries.push((index, parameter.name().to_string(), parameter.dependencies()));
+        }
+
+        ParameterScheduler::new(&entries)
+    }
+
+    /// Compute every registered parameter for this timestep, in `scheduler`'s dependency order,
+    /// storing each result into `parameter_state` as soon as it is computed so that a parameter in
+    /// a later level always reads an up-to-date value.
+    pub fn compute_all<S: SolverSettings>(
+        &self,
+        scheduler: &ParameterScheduler,
+        settings: &S,
+        timestep: &Timestep,
+        scenario_index: &ScenarioIndex,
+        network_state: &NetworkState,
+        parameter_state: &ParameterState,
+    ) -> Result<(), PywrError> {
+        scheduler.evaluate(settings, |node| match node {
+            ParameterType::Parameter(idx) => {
+                let mut parameter = self.parameters[*idx].lock().unwrap();
+                let value = parameter.compute(timestep, scenario_index, network_state, parameter_state)?;
+                parameter_state.set_parameter_value(idx, value)
+            }
+            ParameterType::Index(idx) => {
+                let mut parameter = self.index_parameters[*idx].lock().unwrap();
+                let value = parameter.compute(timestep, scenario_index, network_state, parameter_state)?;
+                parameter_state.set_index_parameter_value(idx, value)
+            }
+        })
+    }
+}
+
+impl Default for ParameterCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,90 @@
+use super::Recorder;
+use crate::model::Model;
+use crate::scenario::ScenarioIndex;
+use crate::schema::PywrModel;
+use crate::timestep::Timestep;
+use crate::PywrError;
+use std::any::Any;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct CsvOutput {
+    pub filename: PathBuf,
+}
+
+struct CsvInternalState {
+    writer: BufWriter<File>,
+    header_written: bool,
+}
+
+impl CsvOutput {
+    pub fn add_to_model(&self, model: &mut Model, _schema: &PywrModel) -> Result<(), PywrError> {
+        model.add_recorder(Box::new(self.clone()))
+    }
+}
+
+impl Recorder for CsvOutput {
+    fn setup(&self, _model: &Model, _schema: &PywrModel) -> Result<Option<Box<dyn Any + Send>>, PywrError> {
+        let file = File::create(&self.filename).map_err(|e| {
+            PywrError::InternalParameterError(format!(
+                "Failed to create CSV output file `{}`: {e}",
+                self.filename.display()
+            ))
+        })?;
+
+        Ok(Some(Box::new(CsvInternalState {
+            writer: BufWriter::new(file),
+            header_written: false,
+        })))
+    }
+
+    fn save(
+        &self,
+        timestep: &Timestep,
+        scenario_index: &ScenarioIndex,
+        values: &[f64],
+        internal_state: &mut Option<Box<dyn Any + Send>>,
+    ) -> Result<(), PywrError> {
+        let state = internal_state_mut(internal_state)?;
+
+        if !state.header_written {
+            let header: Vec<String> = (0..values.len()).map(|i| format!("value_{i}")).collect();
+            writeln!(state.writer, "timestep,scenario,{}", header.join(",")).map_err(csv_io_error)?;
+            state.header_written = true;
+        }
+
+        let row: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        writeln!(
+            state.writer,
+            "{},{},{}",
+            timestep.index, scenario_index.index, row.join(",")
+        )
+        .map_err(csv_io_error)?;
+
+        // Flushed every timestep rather than left in the `BufWriter`'s internal buffer until the
+        // run ends, so a crash mid-run does not lose results that have already been recorded.
+        state.writer.flush().map_err(csv_io_error)
+    }
+
+    fn finalise(&self, internal_state: &mut Option<Box<dyn Any + Send>>) -> Result<(), PywrError> {
+        let state = internal_state_mut(internal_state)?;
+        state.writer.flush().map_err(csv_io_error)
+    }
+}
+
+fn internal_state_mut(internal_state: &mut Option<Box<dyn Any + Send>>) -> Result<&mut CsvInternalState, PywrError> {
+    match internal_state {
+        Some(internal) => internal
+            .downcast_mut::<CsvInternalState>()
+            .ok_or_else(|| PywrError::InternalParameterError("CSV recorder internal state did not downcast to the correct type.".to_string())),
+        None => Err(PywrError::InternalParameterError(
+            "CSV recorder has no internal state defined when one was expected.".to_string(),
+        )),
+    }
+}
+
+fn csv_io_error(e: std::io::Error) -> PywrError {
+    PywrError::InternalParameterError(format!("Error writing CSV output: {e}"))
+}
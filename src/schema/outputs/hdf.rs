@@ -0,0 +1,99 @@
+use super::Recorder;
+use crate::model::Model;
+use crate::scenario::ScenarioIndex;
+use crate::schema::PywrModel;
+use crate::timestep::Timestep;
+use crate::PywrError;
+use ndarray::s;
+use std::any::Any;
+use std::path::PathBuf;
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct Hdf5Output {
+    pub filename: PathBuf,
+}
+
+struct Hdf5InternalState {
+    _file: hdf5::File,
+    dataset: hdf5::Dataset,
+    rows_written: usize,
+}
+
+impl Hdf5Output {
+    pub fn add_to_model(&self, model: &mut Model, _schema: &PywrModel) -> Result<(), PywrError> {
+        model.add_recorder(Box::new(self.clone()))
+    }
+}
+
+impl Recorder for Hdf5Output {
+    fn setup(&self, model: &Model, _schema: &PywrModel) -> Result<Option<Box<dyn Any + Send>>, PywrError> {
+        let file = hdf5::File::create(&self.filename).map_err(|e| {
+            PywrError::InternalParameterError(format!(
+                "Failed to create HDF5 output file `{}`: {e}",
+                self.filename.display()
+            ))
+        })?;
+
+        let num_metrics = model.num_recorded_metrics();
+
+        // The number of rows is not known up-front for a streaming recorder (a run's total
+        // timestep/scenario count is set by the timestepper, not by this recorder), so the
+        // dataset is created empty and extended with `resize` as each timestep's values arrive.
+        let dataset = file
+            .new_dataset::<f64>()
+            .chunk((1, num_metrics))
+            .shape((0.., num_metrics))
+            .create("data")
+            .map_err(|e| PywrError::InternalParameterError(format!("Failed to create HDF5 dataset: {e}")))?;
+
+        Ok(Some(Box::new(Hdf5InternalState {
+            _file: file,
+            dataset,
+            rows_written: 0,
+        })))
+    }
+
+    fn save(
+        &self,
+        _timestep: &Timestep,
+        _scenario_index: &ScenarioIndex,
+        values: &[f64],
+        internal_state: &mut Option<Box<dyn Any + Send>>,
+    ) -> Result<(), PywrError> {
+        let state = internal_state_mut(internal_state)?;
+
+        let row = state.rows_written;
+        state
+            .dataset
+            .resize((row + 1, values.len()))
+            .map_err(|e| PywrError::InternalParameterError(format!("Failed to extend HDF5 dataset: {e}")))?;
+        state
+            .dataset
+            .write_slice(values, s![row, ..])
+            .map_err(|e| PywrError::InternalParameterError(format!("Failed to write HDF5 row: {e}")))?;
+
+        state.rows_written += 1;
+        Ok(())
+    }
+
+    fn finalise(&self, internal_state: &mut Option<Box<dyn Any + Send>>) -> Result<(), PywrError> {
+        let state = internal_state_mut(internal_state)?;
+        state
+            .dataset
+            .file()
+            .map_err(|e| PywrError::InternalParameterError(format!("Failed to flush HDF5 file: {e}")))?
+            .flush()
+            .map_err(|e| PywrError::InternalParameterError(format!("Failed to flush HDF5 file: {e}")))
+    }
+}
+
+fn internal_state_mut(internal_state: &mut Option<Box<dyn Any + Send>>) -> Result<&mut Hdf5InternalState, PywrError> {
+    match internal_state {
+        Some(internal) => internal.downcast_mut::<Hdf5InternalState>().ok_or_else(|| {
+            PywrError::InternalParameterError("HDF5 recorder internal state did not downcast to the correct type.".to_string())
+        }),
+        None => Err(PywrError::InternalParameterError(
+            "HDF5 recorder has no internal state defined when one was expected.".to_string(),
+        )),
+    }
+}
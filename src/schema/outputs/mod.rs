@@ -1,15 +1,51 @@
 mod csv;
 mod hdf;
+mod network;
 
 pub use self::csv::CsvOutput;
+use crate::scenario::ScenarioIndex;
+use crate::timestep::Timestep;
 use crate::PywrError;
 pub use hdf::Hdf5Output;
+pub use network::NetworkOutput;
+use std::any::Any;
+
+/// A streaming output sink.
+///
+/// Unlike the original `Output::add_to_model` recorders, which implicitly accumulated a whole
+/// run's results in memory before writing them out once at the end, a `Recorder` is handed each
+/// timestep's values as they are produced and is expected to flush them incrementally (or batch
+/// and flush them in bounded chunks), keeping memory use flat regardless of run length.
+pub trait Recorder {
+    /// Called once, before the first timestep, to open any files/connections/buffers the recorder
+    /// needs. The returned state is threaded through every subsequent [`Recorder::save`] call and
+    /// into [`Recorder::finalise`].
+    fn setup(
+        &self,
+        model: &crate::model::Model,
+        schema: &crate::schema::PywrModel,
+    ) -> Result<Option<Box<dyn Any + Send>>, PywrError>;
+
+    /// Called once per timestep per scenario with that step's recorded values.
+    fn save(
+        &self,
+        timestep: &Timestep,
+        scenario_index: &ScenarioIndex,
+        values: &[f64],
+        internal_state: &mut Option<Box<dyn Any + Send>>,
+    ) -> Result<(), PywrError>;
+
+    /// Called once after the last timestep, to flush any remaining buffered values and release
+    /// the resources held in `internal_state`.
+    fn finalise(&self, internal_state: &mut Option<Box<dyn Any + Send>>) -> Result<(), PywrError>;
+}
 
 #[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum Output {
     CSV(CsvOutput),
     HDF5(Hdf5Output),
+    Network(NetworkOutput),
 }
 
 impl Output {
@@ -21,6 +57,43 @@ impl Output {
         match self {
             Self::CSV(o) => o.add_to_model(model, schema),
             Self::HDF5(o) => o.add_to_model(model, schema),
+            Self::Network(o) => o.add_to_model(model, schema),
+        }
+    }
+}
+
+impl Recorder for Output {
+    fn setup(
+        &self,
+        model: &crate::model::Model,
+        schema: &crate::schema::PywrModel,
+    ) -> Result<Option<Box<dyn Any + Send>>, PywrError> {
+        match self {
+            Self::CSV(o) => o.setup(model, schema),
+            Self::HDF5(o) => o.setup(model, schema),
+            Self::Network(o) => o.setup(model, schema),
+        }
+    }
+
+    fn save(
+        &self,
+        timestep: &Timestep,
+        scenario_index: &ScenarioIndex,
+        values: &[f64],
+        internal_state: &mut Option<Box<dyn Any + Send>>,
+    ) -> Result<(), PywrError> {
+        match self {
+            Self::CSV(o) => o.save(timestep, scenario_index, values, internal_state),
+            Self::HDF5(o) => o.save(timestep, scenario_index, values, internal_state),
+            Self::Network(o) => o.save(timestep, scenario_index, values, internal_state),
+        }
+    }
+
+    fn finalise(&self, internal_state: &mut Option<Box<dyn Any + Send>>) -> Result<(), PywrError> {
+        match self {
+            Self::CSV(o) => o.finalise(internal_state),
+            Self::HDF5(o) => o.finalise(internal_state),
+            Self::Network(o) => o.finalise(internal_state),
         }
     }
 }
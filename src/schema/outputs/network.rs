@@ -0,0 +1,140 @@
+use super::Recorder;
+use crate::model::Model;
+use crate::scenario::ScenarioIndex;
+use crate::schema::PywrModel;
+use crate::timestep::Timestep;
+use crate::PywrError;
+use std::any::Any;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// One timestep/scenario's recorded values, batched up with its identifying indices before being
+/// sent to [`NetworkOutput::endpoint`].
+#[derive(serde::Serialize)]
+struct Row {
+    timestep: usize,
+    scenario: usize,
+    values: Vec<f64>,
+}
+
+/// Streams batches of recorded results to an external HTTP endpoint, retrying a failed batch with
+/// exponential backoff before giving up.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct NetworkOutput {
+    /// The endpoint batches are POSTed to.
+    pub endpoint: String,
+    /// The number of rows accumulated before a batch is sent. Defaults to 100.
+    #[serde(default = "NetworkOutput::default_batch_size")]
+    pub batch_size: usize,
+    /// The number of times a failed batch is re-sent before giving up. Defaults to 5.
+    #[serde(default = "NetworkOutput::default_max_retries")]
+    pub max_retries: usize,
+    /// The backoff before the first retry; each subsequent retry doubles it. Defaults to 500ms.
+    #[serde(default = "NetworkOutput::default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+}
+
+impl NetworkOutput {
+    fn default_batch_size() -> usize {
+        100
+    }
+
+    fn default_max_retries() -> usize {
+        5
+    }
+
+    fn default_initial_backoff_ms() -> u64 {
+        500
+    }
+
+    pub fn add_to_model(&self, model: &mut Model, _schema: &PywrModel) -> Result<(), PywrError> {
+        model.add_recorder(Box::new(self.clone()))
+    }
+
+    /// Send `batch` to [`Self::endpoint`], retrying with exponential backoff up to
+    /// [`Self::max_retries`] times. A failed attempt updates nothing but the retry counter and the
+    /// backoff; the same batch is re-sent unchanged on the next attempt.
+    fn send_with_retry(&self, client: &reqwest::blocking::Client, batch: &[Row]) -> Result<(), PywrError> {
+        let mut retry = 0;
+        let mut backoff = Duration::from_millis(self.initial_backoff_ms);
+
+        loop {
+            let result = client.post(&self.endpoint).json(&batch).send().and_then(|r| r.error_for_status());
+
+            match result {
+                Ok(_) => return Ok(()),
+                Err(e) if retry < self.max_retries => {
+                    retry += 1;
+                    sleep(backoff);
+                    backoff *= 2;
+                    let _ = e;
+                }
+                Err(e) => {
+                    return Err(PywrError::InternalParameterError(format!(
+                        "Failed to send batch of {} rows to `{}` after {} attempts: {e}",
+                        batch.len(),
+                        self.endpoint,
+                        self.max_retries + 1
+                    )));
+                }
+            }
+        }
+    }
+}
+
+struct NetworkInternalState {
+    client: reqwest::blocking::Client,
+    pending: Vec<Row>,
+}
+
+impl Recorder for NetworkOutput {
+    fn setup(&self, _model: &Model, _schema: &PywrModel) -> Result<Option<Box<dyn Any + Send>>, PywrError> {
+        Ok(Some(Box::new(NetworkInternalState {
+            client: reqwest::blocking::Client::new(),
+            pending: Vec::with_capacity(self.batch_size),
+        })))
+    }
+
+    fn save(
+        &self,
+        timestep: &Timestep,
+        scenario_index: &ScenarioIndex,
+        values: &[f64],
+        internal_state: &mut Option<Box<dyn Any + Send>>,
+    ) -> Result<(), PywrError> {
+        let state = internal_state_mut(internal_state)?;
+
+        state.pending.push(Row {
+            timestep: timestep.index,
+            scenario: scenario_index.index,
+            values: values.to_vec(),
+        });
+
+        if state.pending.len() >= self.batch_size {
+            self.send_with_retry(&state.client, &state.pending)?;
+            state.pending.clear();
+        }
+
+        Ok(())
+    }
+
+    fn finalise(&self, internal_state: &mut Option<Box<dyn Any + Send>>) -> Result<(), PywrError> {
+        let state = internal_state_mut(internal_state)?;
+        if !state.pending.is_empty() {
+            self.send_with_retry(&state.client, &state.pending)?;
+            state.pending.clear();
+        }
+        Ok(())
+    }
+}
+
+fn internal_state_mut(internal_state: &mut Option<Box<dyn Any + Send>>) -> Result<&mut NetworkInternalState, PywrError> {
+    match internal_state {
+        Some(internal) => internal.downcast_mut::<NetworkInternalState>().ok_or_else(|| {
+            PywrError::InternalParameterError("Network recorder internal state did not downcast to the correct type.".to_string())
+        }),
+        None => Err(PywrError::InternalParameterError(
+            "Network recorder has no internal state defined when one was expected.".to_string(),
+        )),
+    }
+}
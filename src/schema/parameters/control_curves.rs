@@ -1,3 +1,4 @@
+use crate::parameters::Interpolation;
 use crate::schema::data_tables::LoadedTableCollection;
 use crate::schema::parameters::{
     DynamicFloatValue, DynamicFloatValueType, IntoV2Parameter, ParameterMeta, TryFromV1Parameter, TryIntoV2Parameter,
@@ -19,6 +20,10 @@ pub struct ControlCurveInterpolatedParameter {
     pub control_curves: Vec<DynamicFloatValue>,
     pub storage_node: String,
     pub values: Vec<DynamicFloatValue>,
+    /// The interpolation scheme used to compute a value between control curve thresholds.
+    /// Defaults to [`Interpolation::Linear`] for backward compatibility with older model files.
+    #[serde(default)]
+    pub interpolation: Interpolation,
 }
 
 impl ControlCurveInterpolatedParameter {
@@ -55,7 +60,13 @@ impl ControlCurveInterpolatedParameter {
             .map(|val| val.load(model, tables, data_path))
             .collect::<Result<_, _>>()?;
 
-        let p = crate::parameters::InterpolatedParameter::new(&self.meta.name, metric, control_curves, values);
+        let p = crate::parameters::InterpolatedParameter::new(
+            &self.meta.name,
+            metric,
+            control_curves,
+            values,
+            self.interpolation,
+        );
         model.add_parameter(Box::new(p))
     }
 }
@@ -91,6 +102,8 @@ impl TryFromV1Parameter<ControlCurveInterpolatedParameterV1> for ControlCurveInt
             control_curves,
             storage_node: v1.storage_node,
             values,
+            // Older v1 models have no concept of interpolation scheme; they always behaved linearly.
+            interpolation: Interpolation::Linear,
         };
         Ok(p)
     }
@@ -281,6 +294,10 @@ pub struct ControlCurvePiecewiseInterpolatedParameter {
     pub values: Option<Vec<[f64; 2]>>,
     pub minimum: Option<f64>,
     pub maximum: Option<f64>,
+    /// The interpolation scheme used to compute a value between control curve thresholds.
+    /// Defaults to [`Interpolation::Linear`] for backward compatibility with older model files.
+    #[serde(default)]
+    pub interpolation: Interpolation,
 }
 
 impl ControlCurvePiecewiseInterpolatedParameter {
@@ -323,6 +340,7 @@ impl ControlCurvePiecewiseInterpolatedParameter {
             values,
             self.maximum.unwrap_or(1.0),
             self.minimum.unwrap_or(0.0),
+            self.interpolation,
         );
         model.add_parameter(Box::new(p))
     }
@@ -359,6 +377,8 @@ impl TryFromV1Parameter<ControlCurvePiecewiseInterpolatedParameterV1> for Contro
             values: v1.values,
             minimum: Some(v1.minimum),
             maximum: None,
+            // Older v1 models have no concept of interpolation scheme; they always behaved linearly.
+            interpolation: Interpolation::Linear,
         };
         Ok(p)
     }
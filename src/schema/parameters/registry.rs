@@ -0,0 +1,163 @@
+use crate::schema::data_tables::LoadedTableCollection;
+use crate::schema::parameters::control_curves::{
+    ControlCurveInterpolatedParameter, ControlCurveParameter, ControlCurvePiecewiseInterpolatedParameter,
+};
+use crate::schema::parameters::rolling_aggregation::RollingAggregationParameter;
+use crate::{ParameterIndex, PywrError};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A builder for a schema parameter of a runtime-registered type.
+///
+/// Given the raw `"type"`-tagged JSON value for a parameter, the table collection it may
+/// reference and the data path it was loaded from, a builder constructs the parameter and
+/// adds it to the model, returning its [`ParameterIndex`].
+pub type ParameterBuilder = Box<
+    dyn Fn(&serde_json::Value, &mut crate::model::Model, &LoadedTableCollection, Option<&Path>) -> Result<ParameterIndex, PywrError>
+        + Send
+        + Sync,
+>;
+
+/// A runtime-extensible registry mapping a schema parameter's `"type"` tag to a builder.
+///
+/// [`Default`] pre-registers every parameter type defined under `src/schema/parameters/` that has
+/// both a `"type"`-tagged schema struct and an `add_to_model` returning a [`ParameterIndex`] (i.e.
+/// everything in this module except [`super::control_curves::ControlCurveIndexParameter`], whose
+/// `add_to_model` returns an `IndexParameterIndex` instead and so cannot implement
+/// [`ParameterBuilder`]). It does *not* cover the lower-level [`crate::parameters`] types (e.g.
+/// `ConstantParameter`, `Array1Parameter`, `SimpleWasmParameter`) — those have no schema-level
+/// `"type"`-tagged struct to deserialize in the first place. Downstream crates can call
+/// [`ParameterRegistry::register`] to add their own parameter types without forking this crate.
+pub struct ParameterRegistry {
+    builders: HashMap<String, ParameterBuilder>,
+}
+
+impl ParameterRegistry {
+    /// Register a builder for a new parameter `"type"` name.
+    ///
+    /// If a builder was already registered under this name it is replaced and returned.
+    pub fn register(&mut self, type_name: &str, builder: ParameterBuilder) -> Option<ParameterBuilder> {
+        self.builders.insert(type_name.to_string(), builder)
+    }
+
+    /// The builder registered for the given `"type"` name, if any.
+    pub fn get(&self, type_name: &str) -> Option<&ParameterBuilder> {
+        self.builders.get(type_name)
+    }
+
+    /// Build and add a parameter of the given `"type"` name to the model.
+    pub fn build(
+        &self,
+        type_name: &str,
+        data: &serde_json::Value,
+        model: &mut crate::model::Model,
+        tables: &LoadedTableCollection,
+        data_path: Option<&Path>,
+    ) -> Result<ParameterIndex, PywrError> {
+        let builder = self
+            .builders
+            .get(type_name)
+            .ok_or_else(|| PywrError::UnrecognisedParameterType(type_name.to_string()))?;
+
+        builder(data, model, tables, data_path)
+    }
+}
+
+/// Deserialize `data` as `T` and hand it to `add_to_model`, wrapping a deserialization failure as
+/// a [`PywrError::InternalParameterError`] so it can cross the [`ParameterBuilder`] boundary.
+fn deserialize_and_add<T, F>(
+    data: &serde_json::Value,
+    model: &mut crate::model::Model,
+    tables: &LoadedTableCollection,
+    data_path: Option<&Path>,
+    add_to_model: F,
+) -> Result<ParameterIndex, PywrError>
+where
+    T: serde::de::DeserializeOwned,
+    F: Fn(&T, &mut crate::model::Model, &LoadedTableCollection, Option<&Path>) -> Result<ParameterIndex, PywrError>,
+{
+    let parameter: T = serde_json::from_value(data.clone())
+        .map_err(|e| PywrError::InternalParameterError(format!("Failed to deserialize parameter: {e}")))?;
+    add_to_model(&parameter, model, tables, data_path)
+}
+
+impl Default for ParameterRegistry {
+    /// Create a new registry pre-populated with all of the built-in parameter types.
+    fn default() -> Self {
+        let mut builders: HashMap<String, ParameterBuilder> = HashMap::new();
+
+        builders.insert(
+            "ControlCurveInterpolated".to_string(),
+            Box::new(|data, model, tables, data_path| {
+                deserialize_and_add::<ControlCurveInterpolatedParameter, _>(
+                    data,
+                    model,
+                    tables,
+                    data_path,
+                    ControlCurveInterpolatedParameter::add_to_model,
+                )
+            }),
+        );
+        builders.insert(
+            "ControlCurve".to_string(),
+            Box::new(|data, model, tables, data_path| {
+                deserialize_and_add::<ControlCurveParameter, _>(data, model, tables, data_path, ControlCurveParameter::add_to_model)
+            }),
+        );
+        builders.insert(
+            "ControlCurvePiecewiseInterpolated".to_string(),
+            Box::new(|data, model, tables, data_path| {
+                deserialize_and_add::<ControlCurvePiecewiseInterpolatedParameter, _>(
+                    data,
+                    model,
+                    tables,
+                    data_path,
+                    ControlCurvePiecewiseInterpolatedParameter::add_to_model,
+                )
+            }),
+        );
+        builders.insert(
+            "RollingAggregation".to_string(),
+            Box::new(|data, model, tables, data_path| {
+                deserialize_and_add::<RollingAggregationParameter, _>(
+                    data,
+                    model,
+                    tables,
+                    data_path,
+                    RollingAggregationParameter::add_to_model,
+                )
+            }),
+        );
+
+        Self { builders }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ParameterRegistry;
+
+    /// Every `"type"`-tagged parameter struct in `src/schema/parameters/` whose `add_to_model`
+    /// returns a `ParameterIndex` must be registered by `Default`. `ControlCurveIndexParameter` is
+    /// deliberately excluded (its `add_to_model` returns an `IndexParameterIndex`) and is not
+    /// listed here.
+    #[test]
+    fn test_default_registers_every_schema_parameter_builder() {
+        let registry = ParameterRegistry::default();
+
+        for type_name in [
+            "ControlCurveInterpolated",
+            "ControlCurve",
+            "ControlCurvePiecewiseInterpolated",
+            "RollingAggregation",
+        ] {
+            assert!(registry.get(type_name).is_some(), "`{type_name}` is not registered by default");
+        }
+    }
+
+    #[test]
+    fn test_unrecognised_type_is_not_registered() {
+        let registry = ParameterRegistry::default();
+        assert!(registry.get("NotARealParameterType").is_none());
+    }
+}
@@ -0,0 +1,99 @@
+use crate::schema::data_tables::LoadedTableCollection;
+use crate::schema::parameters::{
+    DynamicFloatValue, DynamicFloatValueType, IntoV2Parameter, ParameterMeta, TryFromV1Parameter, TryIntoV2Parameter,
+};
+use crate::{ParameterIndex, PywrError};
+use pywr_schema::parameters::RollingWindowParameter as RollingAggregationParameterV1;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub enum RollingAggFunc {
+    Mean,
+    Min,
+    Max,
+    Sum,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct RollingAggregationParameter {
+    #[serde(flatten)]
+    pub meta: ParameterMeta,
+    pub metric: DynamicFloatValue,
+    pub window_size: usize,
+    pub agg_func: RollingAggFunc,
+    pub initial_value: Option<f64>,
+}
+
+impl RollingAggregationParameter {
+    pub fn node_references(&self) -> HashMap<&str, &str> {
+        HashMap::new()
+    }
+
+    pub fn parameters(&self) -> HashMap<&str, DynamicFloatValueType> {
+        let mut attributes = HashMap::new();
+        attributes.insert("metric", (&self.metric).into());
+        attributes
+    }
+
+    pub fn add_to_model(
+        &self,
+        model: &mut crate::model::Model,
+        tables: &LoadedTableCollection,
+        data_path: Option<&Path>,
+    ) -> Result<ParameterIndex, PywrError> {
+        let metric = self.metric.load(model, tables, data_path)?;
+
+        let agg_func = match self.agg_func {
+            RollingAggFunc::Mean => crate::parameters::RollingAggFunc::Mean,
+            RollingAggFunc::Min => crate::parameters::RollingAggFunc::Min,
+            RollingAggFunc::Max => crate::parameters::RollingAggFunc::Max,
+            RollingAggFunc::Sum => crate::parameters::RollingAggFunc::Sum,
+        };
+
+        let p = crate::parameters::RollingAggregationParameter::new(
+            &self.meta.name,
+            metric,
+            self.window_size,
+            agg_func,
+            self.initial_value,
+        );
+        model.add_parameter(Box::new(p))
+    }
+}
+
+impl TryFromV1Parameter<RollingAggregationParameterV1> for RollingAggregationParameter {
+    type Error = PywrError;
+
+    fn try_from_v1_parameter(
+        v1: RollingAggregationParameterV1,
+        parent_node: Option<&str>,
+        unnamed_count: &mut usize,
+    ) -> Result<Self, Self::Error> {
+        let meta: ParameterMeta = v1.meta.into_v2_parameter(parent_node, unnamed_count);
+
+        let metric = v1.metric.try_into_v2_parameter(Some(&meta.name), unnamed_count)?;
+
+        let agg_func = match v1.agg_func.as_str() {
+            "mean" => RollingAggFunc::Mean,
+            "min" => RollingAggFunc::Min,
+            "max" => RollingAggFunc::Max,
+            "sum" => RollingAggFunc::Sum,
+            _ => {
+                return Err(PywrError::V1SchemaConversion(format!(
+                    "RollingAggregationParameter '{}' has an unrecognised agg_func '{}'.",
+                    &meta.name, v1.agg_func
+                )))
+            }
+        };
+
+        let p = Self {
+            meta,
+            metric,
+            window_size: v1.window_size,
+            agg_func,
+            initial_value: v1.initial_value,
+        };
+        Ok(p)
+    }
+}
@@ -0,0 +1,365 @@
+mod profile;
+mod settings;
+
+pub use profile::{PhaseStats, ScenarioProfile, SolverProfiler};
+pub use settings::{ClpMethod, ClpSolverSettings, ClpSolverSettingsBuilder};
+
+use crate::PywrError;
+use profile::PhaseTimer;
+use rayon::prelude::*;
+use std::sync::Mutex;
+
+/// The basis captured from a solved timestep, used to warm-start the following timestep's solve.
+///
+/// CLP represents a basis as one status byte per row and per structural column (basic,
+/// at-lower-bound, at-upper-bound, etc. — see `Clp_getRowStatus`/`Clp_getColumnStatus`). A basis
+/// is re-captured after every warm-start-eligible solve and re-installed before that scenario's
+/// next solve, unless the matrix has been resized in between (see [`WarmStartBasis::install`]).
+#[derive(Clone)]
+struct WarmStartBasis {
+    row_status: Vec<i32>,
+    column_status: Vec<i32>,
+}
+
+impl WarmStartBasis {
+    fn capture(model: *mut clp_sys::Clp_Simplex) -> Self {
+        let num_rows = unsafe { clp_sys::Clp_numberRows(model) };
+        let num_columns = unsafe { clp_sys::Clp_numberColumns(model) };
+
+        let row_status = (0..num_rows)
+            .map(|i| unsafe { clp_sys::Clp_getRowStatus(model, i) })
+            .collect();
+        let column_status = (0..num_columns)
+            .map(|j| unsafe { clp_sys::Clp_getColumnStatus(model, j) })
+            .collect();
+
+        Self { row_status, column_status }
+    }
+
+    /// Returns `false` (and leaves `model` untouched) if this basis was captured against a
+    /// different number of rows/columns, i.e. the matrix has since been resized by adding or
+    /// removing nodes/edges and this basis is no longer valid.
+    fn install(&self, model: *mut clp_sys::Clp_Simplex) -> bool {
+        let num_rows = unsafe { clp_sys::Clp_numberRows(model) };
+        let num_columns = unsafe { clp_sys::Clp_numberColumns(model) };
+
+        if num_rows as usize != self.row_status.len() || num_columns as usize != self.column_status.len() {
+            return false;
+        }
+
+        for (i, status) in self.row_status.iter().enumerate() {
+            unsafe { clp_sys::Clp_setRowStatus(model, i as i32, *status) };
+        }
+        for (j, status) in self.column_status.iter().enumerate() {
+            unsafe { clp_sys::Clp_setColumnStatus(model, j as i32, *status) };
+        }
+
+        true
+    }
+}
+
+/// A thin wrapper around a CLP [`Clp_Simplex`](clp_sys::Clp_Simplex) model that additionally
+/// warm-starts the simplex basis between consecutive timesteps.
+///
+/// One [`WarmStartBasis`] is retained per scenario, since each scenario's model evolves
+/// independently (and scenarios may be solved in parallel). The stored basis for a scenario is
+/// dropped whenever it no longer matches the model's current row/column count, so a resize (e.g.
+/// from adding nodes/edges) simply falls back to a cold solve rather than producing garbage.
+pub struct ClpSolver {
+    settings: ClpSolverSettings,
+    warm_starts: Vec<Option<WarmStartBasis>>,
+    /// One lazily-created `ClpPresolve` handle per scenario, reused across every timestep's solve
+    /// rather than recreated each call — see [`ClpSolver::solve`]'s doc comment for what this does
+    /// and does not save.
+    presolves: Vec<Option<*mut clp_sys::ClpPresolve>>,
+    profiler: Option<SolverProfiler>,
+}
+
+// SAFETY: the raw `ClpPresolve` pointers in `presolves` are only ever dereferenced from inside
+// `ClpSolver::solve`/`Drop::drop`, both of which require `&mut ClpSolver` (or exclusive ownership),
+// so a `ClpSolver` is never accessed from two threads at once. `ChunkedClpSolver` upholds this by
+// keeping each instance behind its own `Mutex`.
+unsafe impl Send for ClpSolver {}
+
+impl Drop for ClpSolver {
+    fn drop(&mut self) {
+        for presolve in self.presolves.iter().flatten() {
+            unsafe { clp_sys::ClpPresolve_delete(*presolve) };
+        }
+    }
+}
+
+/// Whether a basis should be installed/captured around a solve under `settings`. Warm-starting
+/// only applies to the simplex methods (or a barrier solve with `crossover` enabled, since only
+/// then does CLP produce a basis to capture) — a plain barrier solve has no basis to warm-start
+/// from or to.
+fn warm_start_eligible(settings: &ClpSolverSettings) -> bool {
+    settings.warm_start() && !matches!(settings.method(), ClpMethod::Barrier { crossover: false })
+}
+
+impl ClpSolver {
+    pub fn new(settings: ClpSolverSettings, num_scenarios: usize) -> Self {
+        let profiler = settings.profile().then(SolverProfiler::default);
+        Self {
+            settings,
+            warm_starts: vec![None; num_scenarios],
+            presolves: vec![None; num_scenarios],
+            profiler,
+        }
+    }
+
+    /// Time building or updating the constraint matrix for `scenario_index`, recording the
+    /// elapsed time against the profiler if enabled. The run loop should wrap its per-timestep
+    /// matrix update call with this before calling [`ClpSolver::solve`].
+    pub fn time_build<R>(&mut self, scenario_index: usize, f: impl FnOnce() -> R) -> R {
+        let timer = PhaseTimer::start();
+        let result = f();
+        if let Some(profiler) = &mut self.profiler {
+            profiler.record_build(scenario_index, timer.elapsed());
+        }
+        result
+    }
+
+    /// Solve `model` for the given scenario, installing any previously-captured basis first (if
+    /// warm-starting is enabled) and capturing the new optimal basis for next time.
+    ///
+    /// The algorithm used is [`ClpSolverSettings::method`]. Warm-starting only applies to the
+    /// simplex methods (or a barrier solve with `crossover` enabled, since only then does CLP
+    /// produce a basis to capture).
+    ///
+    /// When [`ClpSolverSettings::presolve`] is enabled, `model` is first reduced with
+    /// `ClpPresolve::presolvedModel` (eliminating doubletons, singletons and forced bounds before
+    /// handing the smaller problem to the simplex/barrier call), then `ClpPresolve::postsolve`
+    /// maps the solution back onto `model`. CLP does not expose a way to carry a `ClpPresolve`
+    /// object's *reductions* over to a later, separately-updated model, so `presolvedModel` is
+    /// still re-run from `model`'s current state every call; what *is* reused across timesteps is
+    /// the `ClpPresolve` handle itself (and its configured `presolve_passes`) — it is created once
+    /// per scenario, on that scenario's first presolve-enabled solve, and kept alive for the rest
+    /// of this `ClpSolver`'s life rather than `new`/`delete`d on every call.
+    pub fn solve(&mut self, scenario_index: usize, model: *mut clp_sys::Clp_Simplex) {
+        let warm_start_eligible = warm_start_eligible(&self.settings);
+
+        if warm_start_eligible {
+            if let Some(basis) = &self.warm_starts[scenario_index] {
+                basis.install(model);
+            }
+        }
+
+        let presolve = self.settings.presolve().then(|| {
+            let presolve_passes = self.settings.presolve_passes() as i32;
+            let presolve = *self.presolves[scenario_index].get_or_insert_with(|| unsafe {
+                let presolve = clp_sys::ClpPresolve_new();
+                clp_sys::ClpPresolve_setPresolveActions(presolve, presolve_passes);
+                presolve
+            });
+            let presolved_model = unsafe { clp_sys::ClpPresolve_presolvedModel(presolve, model) };
+            (presolve, presolved_model)
+        });
+
+        // The model actually solved: the presolved problem if presolve produced one, otherwise the
+        // original. A null `presolved_model` means presolve proved the problem infeasible or
+        // reduced it away entirely, in which case we fall back to solving the original directly.
+        let solve_model = presolve
+            .and_then(|(_, presolved_model)| (!presolved_model.is_null()).then_some(presolved_model))
+            .unwrap_or(model);
+
+        let timer = PhaseTimer::start();
+        match self.settings.method() {
+            ClpMethod::PrimalSimplex => unsafe {
+                clp_sys::Clp_primal(solve_model, 0);
+            },
+            ClpMethod::DualSimplex => unsafe {
+                clp_sys::Clp_dual(solve_model, 0);
+            },
+            ClpMethod::Barrier { crossover } => unsafe {
+                clp_sys::Clp_barrier(solve_model, crossover as i32);
+            },
+        }
+
+        if let Some(profiler) = &mut self.profiler {
+            let iterations = unsafe { clp_sys::Clp_numberIterations(solve_model) };
+            profiler.record_solve(scenario_index, timer.elapsed(), iterations.max(0) as u64);
+        }
+
+        if let Some((presolve, presolved_model)) = presolve {
+            unsafe {
+                clp_sys::ClpPresolve_postsolve(presolve, model, 1);
+                if !presolved_model.is_null() {
+                    clp_sys::Clp_deleteModel(presolved_model);
+                }
+            }
+        }
+
+        if warm_start_eligible {
+            self.warm_starts[scenario_index] = Some(WarmStartBasis::capture(model));
+        }
+    }
+
+    /// Discard all stored bases, forcing every scenario back to a cold solve next time. Call this
+    /// whenever the constraint matrix is resized (nodes/edges added or removed).
+    pub fn invalidate_warm_starts(&mut self) {
+        self.warm_starts.iter_mut().for_each(|w| *w = None);
+    }
+
+    /// The accumulated per-scenario build/solve profile, if profiling was enabled via
+    /// [`ClpSolverSettings::profile`].
+    pub fn profiler(&self) -> Option<&SolverProfiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Print the accumulated profile (if [`ClpSolverSettings::profile`] is enabled) via
+    /// [`SolverProfiler::log_report`]. The run loop should call this once, after the last
+    /// timestep, mirroring [`crate::schema::outputs::Recorder::finalise`]'s end-of-run hook.
+    pub fn finalise(&self) {
+        if let Some(profiler) = &self.profiler {
+            profiler.log_report();
+        }
+    }
+}
+
+/// Partitions a scenario ensemble into [`ClpSolverSettings::chunk_size`]-sized groups, giving each
+/// group its own long-lived [`ClpSolver`] so that warm-starting still applies between timesteps
+/// within a chunk.
+///
+/// When [`ClpSolverSettings::parallel`] is enabled, [`ChunkedClpSolver::solve`] solves every
+/// chunk concurrently across a thread pool sized from [`ClpSolverSettings::threads`] (`0` lets
+/// rayon size the pool from the available CPUs); otherwise each chunk is solved in turn on the
+/// calling thread, giving the same result as a single, unchunked [`ClpSolver`].
+pub struct ChunkedClpSolver {
+    settings: ClpSolverSettings,
+    chunks: Vec<Vec<usize>>,
+    solvers: Vec<Mutex<ClpSolver>>,
+    /// Global scenario index -> (chunk index, chunk-local index), so a single scenario can be
+    /// routed to its chunk's solver without the caller needing to know the chunking scheme.
+    scenario_locations: Vec<(usize, usize)>,
+}
+
+impl ChunkedClpSolver {
+    pub fn new(settings: ClpSolverSettings, num_scenarios: usize) -> Self {
+        let chunk_size = settings.chunk_size().get();
+
+        let chunks: Vec<Vec<usize>> = (0..num_scenarios)
+            .collect::<Vec<_>>()
+            .chunks(chunk_size)
+            .map(<[usize]>::to_vec)
+            .collect();
+
+        let mut scenario_locations = vec![(0, 0); num_scenarios];
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            for (local_index, &scenario_index) in chunk.iter().enumerate() {
+                scenario_locations[scenario_index] = (chunk_index, local_index);
+            }
+        }
+
+        let solvers = chunks
+            .iter()
+            .map(|chunk| Mutex::new(ClpSolver::new(settings, chunk.len())))
+            .collect();
+
+        Self {
+            settings,
+            chunks,
+            solvers,
+            scenario_locations,
+        }
+    }
+
+    /// Solve a single scenario's already-built model for the current timestep on its chunk's
+    /// [`ClpSolver`], the entry point [`Solver::solve`](super::Solver::solve) drives a run loop
+    /// through so that `ChunkedClpSolver` is a drop-in replacement for a single, unchunked
+    /// [`ClpSolver`].
+    pub fn solve_scenario(&self, scenario_index: usize, model: *mut clp_sys::Clp_Simplex) {
+        let (chunk_index, local_index) = self.scenario_locations[scenario_index];
+        self.solvers[chunk_index].lock().unwrap().solve(local_index, model);
+    }
+
+    /// Print every chunk's accumulated profile, if profiling was enabled. See
+    /// [`ClpSolver::finalise`].
+    pub fn finalise(&self) {
+        for solver in &self.solvers {
+            solver.lock().unwrap().finalise();
+        }
+    }
+
+    /// Solve every scenario in the ensemble for the current timestep via `build_and_solve`, which
+    /// is given a scenario's global index, its chunk-local index (the one the chunk's
+    /// [`ClpSolver`] warm-starts against) and the chunk's solver, and returns the value to keep for
+    /// that scenario. Results are returned in scenario-index order, regardless of the order chunks
+    /// finish solving in.
+    pub fn solve<R, F>(&self, build_and_solve: F) -> Result<Vec<R>, PywrError>
+    where
+        R: Send,
+        F: Fn(usize, usize, &mut ClpSolver) -> R + Sync,
+    {
+        let solve_chunk = |(chunk, solver): (&Vec<usize>, &Mutex<ClpSolver>)| -> Vec<(usize, R)> {
+            let mut solver = solver.lock().unwrap();
+            chunk
+                .iter()
+                .enumerate()
+                .map(|(local_index, &scenario_index)| (scenario_index, build_and_solve(scenario_index, local_index, &mut solver)))
+                .collect()
+        };
+
+        let mut results: Vec<(usize, R)> = if self.settings.parallel() {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(self.settings.threads())
+                .build()
+                .map_err(|e| PywrError::InternalParameterError(format!("Failed to build scenario solve thread pool: {e}")))?;
+
+            pool.install(|| {
+                self.chunks
+                    .par_iter()
+                    .zip(self.solvers.par_iter())
+                    .flat_map(solve_chunk)
+                    .collect()
+            })
+        } else {
+            self.chunks.iter().zip(self.solvers.iter()).flat_map(solve_chunk).collect()
+        };
+
+        results.sort_by_key(|(scenario_index, _)| *scenario_index);
+        Ok(results.into_iter().map(|(_, r)| r).collect())
+    }
+
+    /// Discard every chunk's warm-started bases, forcing every scenario back to a cold solve next
+    /// time. Call this whenever the constraint matrix is resized (nodes/edges added or removed).
+    pub fn invalidate_warm_starts(&self) {
+        for solver in &self.solvers {
+            solver.lock().unwrap().invalidate_warm_starts();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::warm_start_eligible;
+    use super::{ClpMethod, ClpSolverSettingsBuilder};
+
+    /// `ClpMethod::Barrier` is reachable through [`ClpSolver::solve`] like any other method (it is
+    /// dispatched from the same `match` as [`ClpMethod::PrimalSimplex`]/[`ClpMethod::DualSimplex`]
+    /// and, via `crate::solvers::Solver`, from the run loop's single entry point); what
+    /// differs is only whether a basis is captured/installed around it.
+    #[test]
+    fn barrier_without_crossover_is_not_warm_start_eligible() {
+        let settings = ClpSolverSettingsBuilder::default()
+            .method(ClpMethod::Barrier { crossover: false })
+            .build();
+        assert!(!warm_start_eligible(&settings));
+    }
+
+    #[test]
+    fn barrier_with_crossover_is_warm_start_eligible() {
+        let settings = ClpSolverSettingsBuilder::default()
+            .method(ClpMethod::Barrier { crossover: true })
+            .build();
+        assert!(warm_start_eligible(&settings));
+    }
+
+    #[test]
+    fn simplex_methods_are_warm_start_eligible() {
+        for method in [ClpMethod::PrimalSimplex, ClpMethod::DualSimplex] {
+            let settings = ClpSolverSettingsBuilder::default().method(method).build();
+            assert!(warm_start_eligible(&settings));
+        }
+    }
+}
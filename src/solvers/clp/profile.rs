@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Wall time and call-count totals accumulated for a single phase of a scenario's timesteps
+/// (matrix build/update, or the solve call itself).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PhaseStats {
+    pub total: Duration,
+    pub calls: u64,
+}
+
+impl PhaseStats {
+    fn record(&mut self, elapsed: Duration) {
+        self.total += elapsed;
+        self.calls += 1;
+    }
+}
+
+/// Profiling totals for a single scenario, accumulated over the whole simulation.
+#[derive(Debug, Default, Clone)]
+pub struct ScenarioProfile {
+    /// Time spent building/updating the constraint matrix for this scenario's timesteps.
+    pub build: PhaseStats,
+    /// Time spent inside the simplex/barrier solve call itself.
+    pub solve: PhaseStats,
+    /// Total simplex iterations across every solve for this scenario.
+    pub iterations: u64,
+}
+
+/// Opt-in profiler for [`ClpSolver`](super::ClpSolver), accumulating per-scenario timing and
+/// iteration counts across matrix build/update and solve phases over the whole simulation, so
+/// users can see whether time is dominated by LP construction or by solving.
+#[derive(Debug, Default)]
+pub struct SolverProfiler {
+    scenarios: HashMap<usize, ScenarioProfile>,
+}
+
+impl SolverProfiler {
+    pub fn record_build(&mut self, scenario_index: usize, elapsed: Duration) {
+        self.scenarios.entry(scenario_index).or_default().build.record(elapsed);
+    }
+
+    pub fn record_solve(&mut self, scenario_index: usize, elapsed: Duration, iterations: u64) {
+        let profile = self.scenarios.entry(scenario_index).or_default();
+        profile.solve.record(elapsed);
+        profile.iterations += iterations;
+    }
+
+    /// A structured, per-scenario snapshot of the totals accumulated so far, sorted by scenario
+    /// index, suitable for printing a report or querying programmatically at run end.
+    pub fn report(&self) -> Vec<(usize, ScenarioProfile)> {
+        let mut report: Vec<_> = self.scenarios.iter().map(|(i, p)| (*i, p.clone())).collect();
+        report.sort_by_key(|(i, _)| *i);
+        report
+    }
+
+    /// Print [`Self::report`] as a one-line-per-scenario table. The run loop's finalisation step
+    /// should call this once, after the last timestep, if profiling was enabled.
+    pub fn log_report(&self) {
+        eprintln!("scenario,build_calls,build_secs,solve_calls,solve_secs,iterations");
+        for (scenario_index, profile) in self.report() {
+            eprintln!(
+                "{scenario_index},{},{:.6},{},{:.6},{}",
+                profile.build.calls,
+                profile.build.total.as_secs_f64(),
+                profile.solve.calls,
+                profile.solve.total.as_secs_f64(),
+                profile.iterations,
+            );
+        }
+    }
+}
+
+/// A small RAII-style helper for timing a single phase; read [`PhaseTimer::elapsed`] and record
+/// it against the relevant [`SolverProfiler`] method once the phase completes.
+pub(crate) struct PhaseTimer(Instant);
+
+impl PhaseTimer {
+    pub(crate) fn start() -> Self {
+        Self(Instant::now())
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
@@ -1,4 +1,17 @@
 use crate::solvers::SolverSettings;
+use std::num::NonZeroUsize;
+
+/// The CLP algorithm used to solve each timestep's LP.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum ClpMethod {
+    PrimalSimplex,
+    DualSimplex,
+    /// The interior-point (predictor-corrector) method. For large, densely-connected networks
+    /// solved over a single long horizon this can out-perform simplex. `crossover` additionally
+    /// recovers a basic solution after the barrier solve, which is required to warm-start a
+    /// later simplex re-solve from it.
+    Barrier { crossover: bool },
+}
 
 /// Settings for the OpenCL IPM solvers.
 ///
@@ -7,6 +20,12 @@ use crate::solvers::SolverSettings;
 pub struct ClpSolverSettings {
     parallel: bool,
     threads: usize,
+    warm_start: bool,
+    method: ClpMethod,
+    profile: bool,
+    presolve: bool,
+    presolve_passes: usize,
+    chunk_size: NonZeroUsize,
 }
 
 // Default implementation is a convenience that defers to the builder.
@@ -31,6 +50,43 @@ impl ClpSolverSettings {
     pub fn builder() -> ClpSolverSettingsBuilder {
         ClpSolverSettingsBuilder::default()
     }
+
+    /// Whether the simplex basis from the previous timestep is installed before solving the
+    /// next, rather than solving cold each time.
+    pub fn warm_start(&self) -> bool {
+        self.warm_start
+    }
+
+    /// The algorithm used to solve each timestep's LP.
+    pub fn method(&self) -> ClpMethod {
+        self.method
+    }
+
+    /// Whether the solver accumulates per-scenario build/solve timing and iteration counts for
+    /// later reporting. Off by default, since recording timings has a (small) overhead.
+    pub fn profile(&self) -> bool {
+        self.profile
+    }
+
+    /// Whether `ClpPresolve` is applied to shrink the LP (eliminating doubletons, singletons and
+    /// forced bounds, which Pywr network LPs tend to contain many of) before each solve.
+    pub fn presolve(&self) -> bool {
+        self.presolve
+    }
+
+    /// The maximum number of presolve passes `ClpPresolve` will make.
+    pub fn presolve_passes(&self) -> usize {
+        self.presolve_passes
+    }
+
+    /// The number of scenarios given to each [`ClpSolver`](super::ClpSolver) instance when
+    /// [`Self::parallel`] is enabled. A larger chunk means fewer, longer-lived solvers (less
+    /// thread-pool dispatch overhead, more scenarios sharing one solver, each still warm-starting
+    /// from its own basis);
+    /// a smaller chunk spreads the ensemble more evenly across [`Self::threads`].
+    pub fn chunk_size(&self) -> NonZeroUsize {
+        self.chunk_size
+    }
 }
 
 /// Builder for [`ClpSolverSettings`].
@@ -50,10 +106,20 @@ impl ClpSolverSettings {
 /// builder.parallel();
 /// let settings = builder.build();
 ///
+/// // Warm-starting is on by default; disable it to benchmark against a cold solve.
+/// builder.warm_start(false);
+/// let settings = builder.build();
+///
 /// ```
 pub struct ClpSolverSettingsBuilder {
     parallel: bool,
     threads: usize,
+    warm_start: bool,
+    method: ClpMethod,
+    profile: bool,
+    presolve: bool,
+    presolve_passes: usize,
+    chunk_size: NonZeroUsize,
 }
 
 impl Default for ClpSolverSettingsBuilder {
@@ -61,6 +127,12 @@ impl Default for ClpSolverSettingsBuilder {
         Self {
             parallel: false,
             threads: 0,
+            warm_start: true,
+            method: ClpMethod::DualSimplex,
+            profile: false,
+            presolve: false,
+            presolve_passes: 5,
+            chunk_size: NonZeroUsize::new(1).unwrap(),
         }
     }
 }
@@ -76,24 +148,77 @@ impl ClpSolverSettingsBuilder {
         self
     }
 
+    /// Enable or disable warm-starting the simplex basis between timesteps.
+    pub fn warm_start(&mut self, warm_start: bool) -> &mut Self {
+        self.warm_start = warm_start;
+        self
+    }
+
+    /// Select the algorithm used to solve each timestep's LP.
+    pub fn method(&mut self, method: ClpMethod) -> &mut Self {
+        self.method = method;
+        self
+    }
+
+    /// Enable accumulating per-scenario build/solve timing and iteration counts.
+    pub fn profile(&mut self, profile: bool) -> &mut Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Enable or disable applying `ClpPresolve` before each solve.
+    pub fn presolve(&mut self, presolve: bool) -> &mut Self {
+        self.presolve = presolve;
+        self
+    }
+
+    /// Set the maximum number of presolve passes. Has no effect unless [`Self::presolve`] is
+    /// enabled.
+    pub fn presolve_passes(&mut self, passes: usize) -> &mut Self {
+        self.presolve_passes = passes;
+        self
+    }
+
+    /// Set the number of scenarios solved by each [`ClpSolver`](super::ClpSolver) instance. Has no
+    /// effect unless [`Self::parallel`] is enabled. Defaults to `1`, i.e. every scenario gets its
+    /// own solver; raising it trades some parallelism for less thread-pool dispatch overhead on
+    /// large ensembles.
+    pub fn chunk_size(&mut self, chunk_size: NonZeroUsize) -> &mut Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
     /// Construct a [`ClpSolverSettings`] from the builder.
     pub fn build(&self) -> ClpSolverSettings {
         ClpSolverSettings {
             parallel: self.parallel,
             threads: self.threads,
+            warm_start: self.warm_start,
+            method: self.method,
+            profile: self.profile,
+            presolve: self.presolve,
+            presolve_passes: self.presolve_passes,
+            chunk_size: self.chunk_size,
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ClpSolverSettings, ClpSolverSettingsBuilder};
+    use super::{ClpMethod, ClpSolverSettings, ClpSolverSettingsBuilder};
+    use std::num::NonZeroUsize;
 
     #[test]
     fn builder_test() {
         let settings = ClpSolverSettings {
             parallel: true,
             threads: 0,
+            warm_start: true,
+            method: ClpMethod::DualSimplex,
+            profile: false,
+            presolve: false,
+            presolve_passes: 5,
+            chunk_size: NonZeroUsize::new(1).unwrap(),
         };
         let settings_from_builder = ClpSolverSettingsBuilder::default().parallel().build();
 
@@ -0,0 +1,63 @@
+pub mod clp;
+
+pub use clp::{ChunkedClpSolver, ClpMethod, ClpSolver, ClpSolverSettings, ClpSolverSettingsBuilder};
+
+/// Settings common to every LP solver backend, independent of which one is in use.
+///
+/// Parameter evaluation (`ParameterScheduler::evaluate`) takes any type implementing this trait
+/// rather than a concrete settings type, so it can stay agnostic to which backend produced them.
+pub trait SolverSettings {
+    /// Whether timesteps/scenarios are solved in parallel across [`Self::threads`] worker threads.
+    fn parallel(&self) -> bool;
+    /// The number of worker threads to use when [`Self::parallel`] is enabled. `0` lets the
+    /// underlying thread pool size itself from the available CPUs.
+    fn threads(&self) -> usize;
+}
+
+/// The interface the run loop drives every timestep, regardless of which LP backend is in use.
+///
+/// A `Solver` owns whatever state it needs to carry between timesteps (a warm-started basis, a
+/// presolve handle, accumulated profiling, ...); the run loop's only obligations are to build that
+/// scenario's constraint matrix into `model` and call [`Solver::solve`] once per timestep per
+/// scenario, call [`Solver::invalidate_warm_starts`] whenever it resizes the matrix (adding or
+/// removing nodes/edges), and call [`Solver::finalise`] once after the last timestep.
+pub trait Solver {
+    /// Solve scenario `scenario_index`'s already-built model for the current timestep.
+    fn solve(&mut self, scenario_index: usize, model: *mut clp_sys::Clp_Simplex);
+
+    /// Discard any warm-started state, forcing every scenario back to a cold solve next time.
+    fn invalidate_warm_starts(&mut self);
+
+    /// Called once, after the last timestep, to flush anything accumulated over the run (e.g. a
+    /// profiling report) — the solver-side counterpart to
+    /// [`crate::schema::outputs::Recorder::finalise`].
+    fn finalise(&self);
+}
+
+impl Solver for ClpSolver {
+    fn solve(&mut self, scenario_index: usize, model: *mut clp_sys::Clp_Simplex) {
+        ClpSolver::solve(self, scenario_index, model)
+    }
+
+    fn invalidate_warm_starts(&mut self) {
+        ClpSolver::invalidate_warm_starts(self)
+    }
+
+    fn finalise(&self) {
+        ClpSolver::finalise(self)
+    }
+}
+
+impl Solver for ChunkedClpSolver {
+    fn solve(&mut self, scenario_index: usize, model: *mut clp_sys::Clp_Simplex) {
+        ChunkedClpSolver::solve_scenario(self, scenario_index, model)
+    }
+
+    fn invalidate_warm_starts(&mut self) {
+        ChunkedClpSolver::invalidate_warm_starts(self)
+    }
+
+    fn finalise(&self) {
+        ChunkedClpSolver::finalise(self)
+    }
+}